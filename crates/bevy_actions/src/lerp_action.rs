@@ -1,8 +1,10 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use bevy_physics::{PhysicsAppExt, PhysicsLabel, PhysicsTick};
 use bevy_sequential_actions::*;
 
-use super::IntoValue;
+use super::{plan, IntoValue};
 
 pub(super) struct LerpActionPlugin;
 
@@ -21,6 +23,7 @@ where
     bundle: Option<LerpBundle>,
 }
 
+#[derive(Clone)]
 pub struct LerpConfig<F>
 where
     F: IntoValue<f32>,
@@ -43,6 +46,31 @@ where
     }
 }
 
+impl LerpAction<f32> {
+    // Continues a lerp that started at `from` and has run for `elapsed`
+    pub(crate) fn resume(
+        config: LerpConfig<f32>,
+        agent: Entity,
+        from: Transform,
+        elapsed: Duration,
+    ) -> Self {
+        let mut timer = Timer::from_seconds(config.duration, TimerMode::Once);
+        timer.set_elapsed(elapsed);
+
+        Self {
+            bundle: Some(LerpBundle {
+                lerp: Lerp::new(&config.lerp_type, from),
+                target: LerpTarget(config.target),
+                agent: LerpAgent(agent),
+                timer: LerpTimer(timer),
+            }),
+            config,
+            entity: None,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub enum LerpType {
     Position(Vec3),
     Rotation(Quat),
@@ -55,26 +83,10 @@ where
 {
     fn on_start(&mut self, agent: Entity, world: &mut World, _commands: &mut ActionCommands) {
         let lerp_bundle = self.bundle.take().unwrap_or_else(|| {
-            let lerp_type = match self.config.lerp_type {
-                LerpType::Position(target) => {
-                    let start = world
-                        .get::<Transform>(self.config.target)
-                        .unwrap()
-                        .translation;
-                    Lerp::Position(start, target)
-                }
-                LerpType::Rotation(target) => {
-                    let start = world.get::<Transform>(self.config.target).unwrap().rotation;
-                    Lerp::Rotation(start, target)
-                }
-                LerpType::Transform(target) => {
-                    let start = world.get::<Transform>(self.config.target).unwrap();
-                    Lerp::Transform(start.clone(), target)
-                }
-            };
+            let start = world.get::<Transform>(self.config.target).unwrap();
 
             LerpBundle {
-                lerp: lerp_type,
+                lerp: Lerp::new(&self.config.lerp_type, *start),
                 target: LerpTarget(self.config.target),
                 agent: LerpAgent(agent),
                 timer: LerpTimer(Timer::from_seconds(
@@ -107,20 +119,39 @@ struct LerpBundle {
 }
 
 #[derive(Component)]
-enum Lerp {
+pub(crate) enum Lerp {
     Position(Vec3, Vec3),
     Rotation(Quat, Quat),
     Transform(Transform, Transform),
 }
 
+impl Lerp {
+    fn new(lerp_type: &LerpType, start: Transform) -> Self {
+        match *lerp_type {
+            LerpType::Position(target) => Lerp::Position(start.translation, target),
+            LerpType::Rotation(target) => Lerp::Rotation(start.rotation, target),
+            LerpType::Transform(target) => Lerp::Transform(start, target),
+        }
+    }
+
+    // The part of the start transform being lerped, the rest left as identity
+    pub(crate) fn from(&self) -> Transform {
+        match *self {
+            Lerp::Position(start, _) => Transform::from_translation(start),
+            Lerp::Rotation(start, _) => Transform::from_rotation(start),
+            Lerp::Transform(start, _) => start,
+        }
+    }
+}
+
 #[derive(Component)]
 struct LerpTarget(Entity);
 
 #[derive(Component)]
-struct LerpAgent(Entity);
+pub(crate) struct LerpAgent(pub(crate) Entity);
 
 #[derive(Component)]
-struct LerpTimer(Timer);
+pub(crate) struct LerpTimer(pub(crate) Timer);
 
 fn lerp(
     mut lerp_q: Query<(&mut LerpTimer, &LerpTarget, &Lerp, &LerpAgent)>,
@@ -151,10 +182,10 @@ fn lerp(
             }
 
             if timer.0.finished() {
-                commands.actions(agent.0).next();
+                plan::next(&mut commands, agent.0);
             }
         } else {
-            commands.actions(agent.0).next();
+            plan::next(&mut commands, agent.0);
         }
     }
 }
@@ -5,19 +5,24 @@ pub use bevy_sequential_actions::*;
 
 mod lerp_action;
 mod move_action;
+mod plan;
 mod wait_action;
 
 pub use lerp_action::*;
 pub use move_action::*;
+pub use plan::*;
 pub use wait_action::*;
 
 pub struct ActionsPlugin;
 
 impl Plugin for ActionsPlugin {
     fn build(&self, app: &mut App) {
+        // Only agents whose actions come from an `ActionPlan` survive a
+        // snapshot restore
         app.add_plugin(WaitActionPlugin)
             .add_plugin(MoveActionPlugin)
-            .add_plugin(LerpActionPlugin);
+            .add_plugin(LerpActionPlugin)
+            .add_plugin(PlanPlugin);
     }
 }
 
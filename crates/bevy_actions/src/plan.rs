@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_physics::{SnapshotAppExt, SnapshotData};
+use bevy_sequential_actions::*;
+
+use super::*;
+
+pub(super) struct PlanPlugin;
+
+impl Plugin for PlanPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_snapshot(capture, restore);
+    }
+}
+
+// A cloneable copy of an agent's actions. The queue itself holds boxed actions
+// that can't be cloned, so snapshots capture where the agent is in its plan
+// instead, and restoring rebuilds the queue from there.
+#[derive(Component, Clone)]
+pub struct ActionPlan {
+    actions: Vec<PlannedAction>,
+    repeat: bool,
+    current: usize,
+}
+
+#[derive(Clone)]
+pub enum PlannedAction {
+    Wait(f32),
+    Lerp(LerpConfig<f32>),
+}
+
+// Which action is running and how far along it is, small enough to send over
+// the network
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanState {
+    pub current: usize,
+    pub progress: Option<PlanProgress>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanProgress {
+    Wait { remaining: f32 },
+    Lerp { from: Transform, elapsed: Duration },
+}
+
+impl ActionPlan {
+    pub fn once(actions: Vec<PlannedAction>) -> Self {
+        Self {
+            actions,
+            repeat: false,
+            current: 0,
+        }
+    }
+
+    pub fn repeating(actions: Vec<PlannedAction>) -> Self {
+        Self {
+            actions,
+            repeat: true,
+            current: 0,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.current += 1;
+        if self.repeat && self.current >= self.actions.len() {
+            self.current = 0;
+        }
+    }
+
+    // Queues the remaining actions starting with the current one, which resumes
+    // from `progress` when there is any
+    fn start(&self, agent: Entity, world: &mut World, mut progress: Option<PlanProgress>) {
+        let config = AddConfig {
+            repeat: if self.repeat {
+                Repeat::Forever
+            } else {
+                Repeat::None
+            },
+            ..Default::default()
+        };
+
+        let before = if self.repeat { self.current } else { 0 };
+        let order = (self.current..self.actions.len()).chain(0..before);
+        for index in order {
+            let action: BoxedAction = match (&self.actions[index], progress.take()) {
+                (PlannedAction::Wait(seconds), Some(PlanProgress::Wait { remaining })) => {
+                    Box::new(WaitAction::resume(*seconds, remaining))
+                }
+                (PlannedAction::Wait(seconds), _) => Box::new(WaitAction::new(*seconds)),
+                (PlannedAction::Lerp(lerp_config), Some(PlanProgress::Lerp { from, elapsed })) => {
+                    Box::new(LerpAction::resume(
+                        lerp_config.clone(),
+                        agent,
+                        from,
+                        elapsed,
+                    ))
+                }
+                (PlannedAction::Lerp(lerp_config), _) => {
+                    Box::new(LerpAction::new(lerp_config.clone()))
+                }
+            };
+
+            world.actions(agent).config(config).add(action);
+        }
+    }
+}
+
+pub trait ActionPlanExt {
+    fn plan_actions(&mut self, agent: Entity, plan: ActionPlan) -> &mut Self;
+}
+
+impl ActionPlanExt for Commands<'_, '_> {
+    fn plan_actions(&mut self, agent: Entity, plan: ActionPlan) -> &mut Self {
+        self.add(move |world: &mut World| {
+            plan.start(agent, world, None);
+            world.entity_mut(agent).insert(plan);
+        });
+        self
+    }
+}
+
+// Advances the agent's queue, keeping its plan in step
+pub(crate) fn next(commands: &mut Commands, agent: Entity) {
+    commands.add(move |world: &mut World| {
+        if let Some(mut plan) = world.get_mut::<ActionPlan>(agent) {
+            plan.advance();
+        }
+        world.actions(agent).next();
+    });
+}
+
+// Where every agent with a plan currently is
+pub fn plan_states(world: &mut World) -> Vec<(Entity, PlanState)> {
+    let mut wait_q = world.query::<(&Wait, &wait_action::Agent)>();
+    let mut progress: Vec<(Entity, PlanProgress)> = wait_q
+        .iter(world)
+        .map(|(wait, agent)| (agent.0, PlanProgress::Wait { remaining: wait.0 }))
+        .collect();
+
+    let mut lerp_q = world.query::<(&Lerp, &LerpTimer, &LerpAgent)>();
+    progress.extend(lerp_q.iter(world).map(|(lerp, timer, agent)| {
+        let progress = PlanProgress::Lerp {
+            from: lerp.from(),
+            elapsed: timer.0.elapsed(),
+        };
+        (agent.0, progress)
+    }));
+
+    let mut plan_q = world.query::<(Entity, &ActionPlan)>();
+    plan_q
+        .iter(world)
+        .map(|(agent, plan)| {
+            let progress = progress
+                .iter()
+                .find(|(running, _)| *running == agent)
+                .map(|(_, progress)| *progress);
+            let state = PlanState {
+                current: plan.current,
+                progress,
+            };
+            (agent, state)
+        })
+        .collect()
+}
+
+// Rebuilds the agent's queue so it continues from `state`
+pub fn set_plan_state(world: &mut World, agent: Entity, state: PlanState) {
+    let plan = match world.get_mut::<ActionPlan>(agent) {
+        Some(mut plan) if state.current <= plan.actions.len() => {
+            plan.current = state.current;
+            plan.clone()
+        }
+        _ => return,
+    };
+
+    world.actions(agent).clear();
+    plan.start(agent, world, state.progress);
+}
+
+fn capture(world: &mut World) -> SnapshotData {
+    Box::new(plan_states(world))
+}
+
+fn restore(world: &mut World, data: &SnapshotData) {
+    if let Some(states) = data.downcast_ref::<Vec<(Entity, PlanState)>>() {
+        for (agent, state) in states {
+            set_plan_state(world, *agent, *state);
+        }
+    }
+}
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_physics::{PhysicsAppExt, PhysicsLabel, PhysicsTick};
 use bevy_sequential_actions::*;
 
-use super::IntoValue;
+use super::{plan, IntoValue};
 
 pub struct WaitActionPlugin;
 
@@ -34,6 +34,17 @@ where
     }
 }
 
+impl WaitAction<f32> {
+    // Starts with `remaining` seconds left, as if it had been paused
+    pub(crate) fn resume(seconds: f32, remaining: f32) -> Self {
+        Self {
+            duration: seconds,
+            entity: None,
+            current: Some(remaining),
+        }
+    }
+}
+
 impl<F> Action for WaitAction<F>
 where
     F: IntoValue<f32>,
@@ -68,17 +79,17 @@ struct WaitBundle {
 }
 
 #[derive(Component)]
-struct Wait(f32);
+pub(crate) struct Wait(pub(crate) f32);
 
 #[derive(Component)]
-struct Agent(Entity);
+pub(crate) struct Agent(pub(crate) Entity);
 
 fn wait(mut wait_q: Query<(&mut Wait, &Agent)>, mut commands: Commands, tick: Res<PhysicsTick>) {
     for (mut wait, agent) in wait_q.iter_mut() {
         wait.0 -= tick.delta();
 
         if wait.0 <= 0.0 {
-            commands.actions(agent.0).next();
+            plan::next(&mut commands, agent.0);
         }
     }
 }
@@ -68,6 +68,8 @@ pub enum MaterialName {
     DarkGray,
     Cyan,
     MidnightBlue,
+    Orange,
+    Water,
 }
 
 impl MaterialName {
@@ -81,6 +83,8 @@ impl MaterialName {
             MaterialName::DarkGray => Color::DARK_GRAY,
             MaterialName::Cyan => Color::CYAN,
             MaterialName::MidnightBlue => Color::MIDNIGHT_BLUE,
+            MaterialName::Orange => Color::ORANGE,
+            MaterialName::Water => Color::rgba(0.1, 0.4, 0.8, 0.5),
         }
     }
 
@@ -94,6 +98,8 @@ impl MaterialName {
             MaterialName::DarkGray,
             MaterialName::Cyan,
             MaterialName::MidnightBlue,
+            MaterialName::Orange,
+            MaterialName::Water,
         ]
         .into_iter()
     }
@@ -105,9 +111,7 @@ fn load_assets(
     mut my_assets: ResMut<MyAssets>,
 ) {
     for mesh in MeshName::iter() {
-        my_assets
-            .meshes
-            .insert(mesh, mesh_assets.add(mesh.mesh().into()));
+        my_assets.meshes.insert(mesh, mesh_assets.add(mesh.mesh()));
     }
 
     for mat in MaterialName::iter() {
@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::non_negative;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Scalars {
+    pub speed: f32,
+    pub acceleration: f32,
+    pub damping: f32,
+    pub gravity: f32,
+    pub jump_height: f32,
+}
+
+impl Scalars {
+    pub fn validate(&self) -> Result<(), String> {
+        non_negative("speed", self.speed)?;
+        non_negative("acceleration", self.acceleration)?;
+        non_negative("damping", self.damping)?;
+        non_negative("gravity", self.gravity)?;
+        non_negative("jump_height", self.jump_height)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Ease {
+    #[default]
+    Linear,
+    SmoothStep,
+    In,
+    Out,
+}
+
+impl Ease {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::SmoothStep => 3.0 * t * t - 2.0 * t * t * t,
+            Ease::In => t * t,
+            Ease::Out => t * (2.0 - t),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Transition {
+    pub duration: f32,
+    pub ease: Ease,
+}
+
+impl Transition {
+    pub const INSTANT: Self = Self::new(0.0, Ease::Linear);
+
+    pub const fn new(duration: f32, ease: Ease) -> Self {
+        Self { duration, ease }
+    }
+
+    fn blend(&self, from: f32, to: f32, elapsed: f32) -> f32 {
+        if elapsed >= self.duration {
+            return to;
+        }
+
+        from + (to - from) * self.ease.apply(elapsed / self.duration)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ScalarTransitions {
+    pub speed: Transition,
+    pub acceleration: Transition,
+    pub damping: Transition,
+    pub gravity: Transition,
+    pub jump_height: Transition,
+}
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct GroundTransitions {
+    pub ground: ScalarTransitions,
+    pub enter_air: ScalarTransitions,
+    pub leave_air: ScalarTransitions,
+}
+
+impl Default for GroundTransitions {
+    fn default() -> Self {
+        Self {
+            ground: ScalarTransitions {
+                speed: Transition::new(0.3, Ease::SmoothStep),
+                acceleration: Transition::new(0.2, Ease::SmoothStep),
+                damping: Transition::new(0.3, Ease::SmoothStep),
+                gravity: Transition::INSTANT,
+                jump_height: Transition::INSTANT,
+            },
+            enter_air: ScalarTransitions {
+                speed: Transition::new(0.5, Ease::Linear),
+                acceleration: Transition::new(0.1, Ease::Out),
+                damping: Transition::new(0.1, Ease::Out),
+                gravity: Transition::new(0.15, Ease::In),
+                jump_height: Transition::INSTANT,
+            },
+            leave_air: ScalarTransitions {
+                speed: Transition::new(0.2, Ease::Out),
+                acceleration: Transition::new(0.1, Ease::Out),
+                damping: Transition::new(0.15, Ease::In),
+                gravity: Transition::INSTANT,
+                jump_height: Transition::INSTANT,
+            },
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct ScalarBlend {
+    from: Scalars,
+    to: Scalars,
+    transitions: ScalarTransitions,
+    elapsed: f32,
+    airborne: bool,
+}
+
+impl ScalarBlend {
+    pub fn new(scalars: Scalars) -> Self {
+        Self {
+            from: scalars,
+            to: scalars,
+            transitions: ScalarTransitions::default(),
+            elapsed: 0.0,
+            airborne: false,
+        }
+    }
+
+    pub fn current(&self) -> Scalars {
+        let t = &self.transitions;
+        Scalars {
+            speed: t.speed.blend(self.from.speed, self.to.speed, self.elapsed),
+            acceleration: t.acceleration.blend(
+                self.from.acceleration,
+                self.to.acceleration,
+                self.elapsed,
+            ),
+            damping: t
+                .damping
+                .blend(self.from.damping, self.to.damping, self.elapsed),
+            gravity: t
+                .gravity
+                .blend(self.from.gravity, self.to.gravity, self.elapsed),
+            jump_height: t.jump_height.blend(
+                self.from.jump_height,
+                self.to.jump_height,
+                self.elapsed,
+            ),
+        }
+    }
+
+    pub fn start(&mut self, to: Scalars, airborne: bool, transitions: &GroundTransitions) {
+        self.from = self.current();
+        self.to = to;
+        self.transitions = match (self.airborne, airborne) {
+            (false, true) => transitions.enter_air,
+            (true, false) => transitions.leave_air,
+            _ => transitions.ground,
+        };
+        self.elapsed = 0.0;
+        self.airborne = airborne;
+    }
+
+    pub fn step(&mut self, delta: f32) -> Scalars {
+        self.elapsed += delta;
+        self.current()
+    }
+}
@@ -2,15 +2,48 @@ use bevy::prelude::*;
 use bevy_actions::*;
 use bevy_physics::*;
 
-use crate::assets::*;
+use crate::{assets::*, GravityWell, SurfaceMaterial, SurfaceName};
 
 #[derive(Component)]
 pub enum Block {
     Ground,
     Ground2,
     Ice,
+    Skate,
+    Conveyor(Vec3),
+    BouncePad(BouncePad),
+    Planetoid,
     Spinner,
     Cube,
+    Ladder,
+    Water,
+}
+
+#[derive(Component)]
+pub struct Climbable;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BouncePad {
+    pub height: f32,
+    pub direction: Vec3,
+}
+
+impl Default for BouncePad {
+    fn default() -> Self {
+        Self {
+            height: 8.0,
+            direction: Vec3::Y,
+        }
+    }
+}
+
+impl BouncePad {
+    pub fn launch_velocity(&self, gravity: f32, up: Vec3, boost: f32) -> Vec3 {
+        // Scaled so the apex is `height` above the pad whatever the direction.
+        let direction = self.direction.normalize();
+        let vertical = f32::sqrt(2.0 * gravity * self.height * boost);
+        direction * vertical / direction.dot(up).max(0.1)
+    }
 }
 
 pub trait SpawnBlockExt {
@@ -29,6 +62,7 @@ impl SpawnBlockExt for Commands<'_, '_> {
                         ..Default::default()
                     },
                     Block::Ground,
+                    SurfaceMaterial::default(),
                     RigidBody::Fixed,
                     Collider::cuboid(0.5, 0.5, 0.5),
                     CollisionGroups::from(PhysicsLayer::PLATFORM),
@@ -45,6 +79,7 @@ impl SpawnBlockExt for Commands<'_, '_> {
                         ..Default::default()
                     },
                     Block::Ground,
+                    SurfaceMaterial::default(),
                     RigidBody::Fixed,
                     Collider::cuboid(0.5, 0.5, 0.5),
                     CollisionGroups::from(PhysicsLayer::PLATFORM),
@@ -61,6 +96,9 @@ impl SpawnBlockExt for Commands<'_, '_> {
                         ..Default::default()
                     },
                     Block::Ice,
+                    // Controllers fill in the tuned material by name
+                    SurfaceName::from("ice"),
+                    SurfaceMaterial::default(),
                     RigidBody::Fixed,
                     Collider::cuboid(0.5, 0.5, 0.5),
                     CollisionGroups::from(PhysicsLayer::PLATFORM),
@@ -68,11 +106,95 @@ impl SpawnBlockExt for Commands<'_, '_> {
                     Restitution::coefficient(0.0),
                 ));
             }
+            Block::Skate => {
+                self.spawn((
+                    PbrBundle {
+                        mesh: assets.mesh(MeshName::Cube),
+                        material: assets.material(MaterialName::MidnightBlue),
+                        transform,
+                        ..Default::default()
+                    },
+                    Block::Skate,
+                    SurfaceName::from("skate"),
+                    SurfaceMaterial::default(),
+                    RigidBody::Fixed,
+                    Collider::cuboid(0.5, 0.5, 0.5),
+                    CollisionGroups::from(PhysicsLayer::PLATFORM),
+                    Friction::coefficient(0.0),
+                    Restitution::coefficient(0.0),
+                ));
+            }
+            Block::Conveyor(velocity) => {
+                self.spawn((
+                    PbrBundle {
+                        mesh: assets.mesh(MeshName::Cube),
+                        material: assets.material(MaterialName::Orange),
+                        transform,
+                        ..Default::default()
+                    },
+                    Block::Conveyor(velocity),
+                    SurfaceMaterial {
+                        velocity,
+                        ..Default::default()
+                    },
+                    RigidBody::Fixed,
+                    Collider::cuboid(0.5, 0.5, 0.5),
+                    CollisionGroups::from(PhysicsLayer::PLATFORM),
+                    Friction::coefficient(0.3),
+                    Restitution::coefficient(0.0),
+                ));
+            }
+            Block::BouncePad(bounce_pad) => {
+                self.spawn((
+                    PbrBundle {
+                        mesh: assets.mesh(MeshName::Cube),
+                        material: assets.material(MaterialName::Red),
+                        transform,
+                        ..Default::default()
+                    },
+                    Block::BouncePad(bounce_pad),
+                    bounce_pad,
+                    SurfaceMaterial::default(),
+                    RigidBody::Fixed,
+                    Collider::cuboid(0.5, 0.5, 0.5),
+                    CollisionGroups::from(PhysicsLayer::PLATFORM),
+                    Friction::coefficient(0.3),
+                    Restitution::coefficient(0.0),
+                ));
+            }
+            Block::Planetoid => {
+                self.spawn((
+                    PbrBundle {
+                        mesh: assets.mesh(MeshName::Icosphere),
+                        material: assets.material(MaterialName::SeaGreen),
+                        transform,
+                        ..Default::default()
+                    },
+                    Block::Planetoid,
+                    SurfaceMaterial::default(),
+                    RigidBody::Fixed,
+                    Collider::ball(1.0),
+                    CollisionGroups::from(PhysicsLayer::PLATFORM),
+                    Friction::coefficient(0.3),
+                    Restitution::coefficient(0.0),
+                ));
+
+                // The well reaches out to twice the planetoid's radius.
+                self.spawn((
+                    TransformBundle::from(transform.with_scale(transform.scale * 2.0)),
+                    GravityWell,
+                    RigidBody::Fixed,
+                    Collider::ball(1.0),
+                    CollisionGroups::from(PhysicsLayer::PLATFORM),
+                    Sensor,
+                ));
+            }
             Block::Spinner => {
                 let spinner_sim = self
                     .spawn((
                         TransformBundle::from_transform(transform),
                         Block::Spinner,
+                        SurfaceMaterial::default(),
                         RigidBody::KinematicVelocityBased,
                         Collider::cuboid(0.5, 0.5, 0.5),
                         CollisionGroups::from(PhysicsLayer::PLATFORM),
@@ -108,6 +230,7 @@ impl SpawnBlockExt for Commands<'_, '_> {
                         TransformBundle::from_transform(transform),
                         ActionsBundle::new(),
                         Block::Cube,
+                        SurfaceMaterial::default(),
                         RigidBody::KinematicPositionBased,
                         Collider::cuboid(0.5, 0.5, 0.5),
                         CollisionGroups::from(PhysicsLayer::BLOCK),
@@ -130,23 +253,54 @@ impl SpawnBlockExt for Commands<'_, '_> {
                     },
                 ));
 
-                self.actions(block_sim)
-                    .config(AddConfig {
-                        repeat: Repeat::Forever,
+                self.plan_actions(
+                    block_sim,
+                    ActionPlan::repeating(vec![
+                        PlannedAction::Wait(1.0),
+                        PlannedAction::Lerp(LerpConfig {
+                            target: block_sim,
+                            lerp_type: LerpType::Position(end),
+                            duration: 2.0,
+                        }),
+                        PlannedAction::Wait(1.0),
+                        PlannedAction::Lerp(LerpConfig {
+                            target: block_sim,
+                            lerp_type: LerpType::Position(start),
+                            duration: 2.0,
+                        }),
+                    ]),
+                );
+            }
+            Block::Ladder => {
+                self.spawn((
+                    PbrBundle {
+                        mesh: assets.mesh(MeshName::Cube),
+                        material: assets.material(MaterialName::Silver),
+                        transform,
                         ..Default::default()
-                    })
-                    .add(WaitAction::new(1.0))
-                    .add(LerpAction::new(LerpConfig {
-                        target: block_sim,
-                        lerp_type: LerpType::Position(end),
-                        duration: 2.0,
-                    }))
-                    .add(WaitAction::new(1.0))
-                    .add(LerpAction::new(LerpConfig {
-                        target: block_sim,
-                        lerp_type: LerpType::Position(start),
-                        duration: 2.0,
-                    }));
+                    },
+                    Block::Ladder,
+                    Climbable,
+                    RigidBody::Fixed,
+                    Collider::cuboid(0.5, 0.5, 0.5),
+                    CollisionGroups::from(PhysicsLayer::PLATFORM),
+                    Sensor,
+                ));
+            }
+            Block::Water => {
+                self.spawn((
+                    PbrBundle {
+                        mesh: assets.mesh(MeshName::Cube),
+                        material: assets.material(MaterialName::Water),
+                        transform,
+                        ..Default::default()
+                    },
+                    Block::Water,
+                    RigidBody::Fixed,
+                    Collider::cuboid(0.5, 0.5, 0.5),
+                    CollisionGroups::from(PhysicsLayer::PLATFORM),
+                    Sensor,
+                ));
             }
         }
 
@@ -5,6 +5,7 @@ pub struct CameraPlugin;
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_startup_system_to_stage(StartupStage::PreStartup, spawn_camera)
+            .add_system_to_stage(CoreStage::Update, camera_orbit)
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 camera_follow.after(bevy::transform::transform_propagate_system),
@@ -65,3 +66,24 @@ fn camera_follow(
         }
     }
 }
+
+fn camera_orbit(
+    mut pivot_q: Query<&mut Transform, With<CameraPivot>>,
+    keyboard: Res<Input<KeyCode>>,
+    time: Res<Time>,
+) {
+    const ORBIT_SPEED: f32 = 2.0;
+
+    let mut orbit = 0.0;
+    if keyboard.pressed(KeyCode::Q) {
+        orbit += 1.0;
+    }
+    if keyboard.pressed(KeyCode::E) {
+        orbit -= 1.0;
+    }
+
+    if orbit != 0.0 {
+        let mut transform = pivot_q.single_mut();
+        transform.rotate_y(orbit * ORBIT_SPEED * time.delta_seconds());
+    }
+}
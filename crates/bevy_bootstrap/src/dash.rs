@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+const DASH_DISTANCE: f32 = 5.0;
+const DASH_TICKS: u32 = 4;
+const DASH_COOLDOWN: f32 = 0.5;
+const DASH_CHARGES: u32 = 2;
+
+#[derive(Component, Clone)]
+pub struct Dash {
+    direction: Vec3,
+    ticks: u32,
+    cooldown: f32,
+    charges: u32,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::ZERO,
+            ticks: 0,
+            cooldown: 0.0,
+            charges: DASH_CHARGES,
+        }
+    }
+}
+
+pub enum DashStep {
+    Idle,
+    Active(Vec3),
+    Finished(Vec3),
+}
+
+impl Dash {
+    pub fn is_active(&self) -> bool {
+        self.ticks > 0
+    }
+
+    pub fn start(&mut self, direction: Vec3) -> bool {
+        if self.is_active() || self.cooldown > 0.0 || self.charges == 0 {
+            return false;
+        }
+
+        let direction = direction.normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return false;
+        }
+
+        self.direction = direction;
+        self.ticks = DASH_TICKS + 1;
+        self.cooldown = DASH_COOLDOWN;
+        self.charges -= 1;
+
+        true
+    }
+
+    pub fn step(&mut self, delta: f32) -> DashStep {
+        self.cooldown = (self.cooldown - delta).max(0.0);
+
+        if !self.is_active() {
+            return DashStep::Idle;
+        }
+
+        self.ticks -= 1;
+
+        if self.ticks == 0 {
+            DashStep::Finished(self.direction)
+        } else {
+            DashStep::Active(self.direction * DASH_DISTANCE / (DASH_TICKS as f32 * delta))
+        }
+    }
+
+    pub fn refill(&mut self) {
+        if !self.is_active() {
+            self.charges = DASH_CHARGES;
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::{
+    prelude::*,
+    render::{mesh::PrimitiveTopology, view::NoFrustumCulling},
+};
+use bevy_physics::*;
+
+// Press F3 to toggle debug lines and physics collider wireframes
+pub struct DebugDrawPlugin;
+
+impl Plugin for DebugDrawPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugDraw>()
+            .add_startup_system(spawn_debug_lines)
+            .add_system_to_stage(CoreStage::PreUpdate, toggle_debug_draw)
+            .add_system_to_stage(CoreStage::Last, update_debug_lines);
+    }
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+// Lines collected over a frame and drawn at the end of it. Nothing is collected
+// while disabled.
+#[derive(Resource, Default)]
+pub struct DebugDraw {
+    enabled: bool,
+    positions: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+}
+
+impl DebugDraw {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Color) {
+        if !self.enabled {
+            return;
+        }
+
+        self.positions.push(start.to_array());
+        self.positions.push(end.to_array());
+        self.colors.push(color.as_linear_rgba_f32());
+        self.colors.push(color.as_linear_rgba_f32());
+    }
+
+    pub fn ray(&mut self, origin: Vec3, vector: Vec3, color: Color) {
+        self.line(origin, origin + vector, color);
+    }
+}
+
+#[derive(Component)]
+struct DebugLines;
+
+fn spawn_debug_lines(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::new(PrimitiveTopology::LineList)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                ..Default::default()
+            }),
+            visibility: Visibility::INVISIBLE,
+            ..Default::default()
+        },
+        // The bounds change every frame
+        NoFrustumCulling,
+        DebugLines,
+    ));
+}
+
+fn toggle_debug_draw(
+    keyboard: Res<Input<KeyCode>>,
+    mut debug_draw: ResMut<DebugDraw>,
+    physics_debug: Option<ResMut<PhysicsDebugRender>>,
+) {
+    if !keyboard.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+
+    debug_draw.enabled = !debug_draw.enabled;
+    if let Some(mut physics_debug) = physics_debug {
+        physics_debug.enabled = debug_draw.enabled;
+    }
+}
+
+fn update_debug_lines(
+    mut lines_q: Query<(&Handle<Mesh>, &mut Visibility), With<DebugLines>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut debug_draw: ResMut<DebugDraw>,
+) {
+    let debug_draw = &mut *debug_draw;
+    let visible = debug_draw.enabled && !debug_draw.positions.is_empty();
+
+    for (handle, mut visibility) in &mut lines_q {
+        if visibility.is_visible != visible {
+            visibility.is_visible = visible;
+        }
+
+        if !visible {
+            continue;
+        }
+
+        if let Some(mesh) = meshes.get_mut(handle) {
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_POSITION,
+                std::mem::take(&mut debug_draw.positions),
+            );
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_COLOR,
+                std::mem::take(&mut debug_draw.colors),
+            );
+        }
+    }
+
+    debug_draw.positions.clear();
+    debug_draw.colors.clear();
+}
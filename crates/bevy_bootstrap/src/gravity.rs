@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy_physics::*;
+
+pub struct GravityPlugin;
+
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(PhysicsLabel::PreUpdate, gravity_wells)
+            .add_snapshot_component::<GravityField>();
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum GravityField {
+    Direction(Vec3),
+    Point(Vec3),
+}
+
+impl Default for GravityField {
+    fn default() -> Self {
+        Self::Direction(-Vec3::Y)
+    }
+}
+
+impl GravityField {
+    pub fn direction(&self, position: Vec3) -> Vec3 {
+        match self {
+            GravityField::Direction(direction) => direction.normalize(),
+            GravityField::Point(center) => (*center - position).try_normalize().unwrap_or(-Vec3::Y),
+        }
+    }
+
+    pub fn up(&self, position: Vec3) -> Vec3 {
+        -self.direction(position)
+    }
+}
+
+#[derive(Component)]
+pub struct GravityWell;
+
+fn gravity_wells(
+    mut field_q: Query<(Entity, &mut GravityField)>,
+    well_q: Query<&GlobalTransform, With<GravityWell>>,
+    physics: Res<PhysicsContext>,
+) {
+    for (entity, mut field) in &mut field_q {
+        let well = physics
+            .intersections_with(entity)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .find_map(|(e1, e2, _)| well_q.get(if e1 == entity { e2 } else { e1 }).ok());
+
+        let new_field = match (well, *field) {
+            (Some(well), _) => GravityField::Point(well.translation()),
+            // Leaving a well falls back to regular gravity.
+            (None, GravityField::Point(_)) => GravityField::default(),
+            (None, direction) => direction,
+        };
+
+        if *field != new_field {
+            *field = new_field;
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::{app::PluginGroupBuilder, prelude::*};
+
+// What the simulation needs on top of `MinimalPlugins` when running without a
+// window or renderer, for apps that drive `App::update` themselves. Rapier
+// looks up scene assets for its async colliders, so those are registered too.
+pub struct HeadlessPlugins;
+
+impl PluginGroup for HeadlessPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(bevy::transform::TransformPlugin)
+            .add(bevy::hierarchy::HierarchyPlugin)
+            .add(bevy::input::InputPlugin)
+            .add(WindowPlugin {
+                add_primary_window: false,
+                exit_on_all_closed: false,
+                ..Default::default()
+            })
+            .add(AssetPlugin::default())
+            .add(HeadlessAssetsPlugin)
+    }
+}
+
+struct HeadlessAssetsPlugin;
+
+impl Plugin for HeadlessAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .add_asset::<Scene>();
+    }
+}
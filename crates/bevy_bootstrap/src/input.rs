@@ -1,34 +1,31 @@
 use bevy::prelude::*;
+use bevy_extensions::*;
+use bevy_physics::*;
+
+use crate::{CameraMain, InputReplay};
 
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(InputAction::default())
-            .insert_resource(InputMovement::default())
+        app.insert_resource(InputMovement::default())
             .add_system_set_to_stage(
                 CoreStage::PreUpdate,
-                SystemSet::new().with_system(action).with_system(movement),
+                SystemSet::new()
+                    .with_system(movement)
+                    .with_system(intent.after(movement))
+                    .with_system(direction.after(intent)),
             );
     }
 }
 
-#[derive(Resource, Default, PartialEq, Eq)]
-pub enum InputAction {
-    #[default]
-    None,
-    Jump,
-}
+pub struct InputTickPlugin;
 
-fn action(keyboard: Res<Input<KeyCode>>, mut input_action: ResMut<InputAction>) {
-    let mut action = InputAction::None;
-
-    if keyboard.just_pressed(KeyCode::Space) {
-        action = InputAction::Jump;
-    }
-
-    if *input_action != action {
-        *input_action = action;
+impl Plugin for InputTickPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(PhysicsLabel::PostUpdate, consume_intents)
+            .add_snapshot_component::<MovementIntent>()
+            .add_snapshot_component::<InputDirection>();
     }
 }
 
@@ -75,3 +72,180 @@ fn movement(keyboard: Res<Input<KeyCode>>, mut input_movement: ResMut<InputMovem
 
     input_movement.0 = input.normalize_or_zero();
 }
+
+#[derive(Component)]
+pub struct LocallyControlled;
+
+// Edges are latched until consumed at the end of a physics tick, so a press is
+// seen by exactly one tick no matter how many frames or ticks run around it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonState {
+    held: bool,
+    pressed: bool,
+    released: bool,
+}
+
+impl ButtonState {
+    pub fn held(&self) -> bool {
+        self.held
+    }
+
+    pub fn just_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    pub fn just_released(&self) -> bool {
+        self.released
+    }
+
+    pub fn latch(&mut self, held: bool, pressed: bool, released: bool) {
+        self.pressed |= pressed || (held && !self.held);
+        self.released |= released || (!held && self.held);
+        self.held = held;
+    }
+
+    pub fn set(&mut self, held: bool) {
+        self.latch(held, false, false);
+    }
+
+    pub fn consume(&mut self) {
+        self.pressed = false;
+        self.released = false;
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.held as u8 | (self.pressed as u8) << 1 | (self.released as u8) << 2
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            held: bits & 1 != 0,
+            pressed: bits & 2 != 0,
+            released: bits & 4 != 0,
+        }
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct MovementIntent {
+    pub movement: Vec2,
+    pub jump: ButtonState,
+    pub dash: ButtonState,
+    pub crouch: ButtonState,
+    pub sprint: ButtonState,
+}
+
+impl MovementIntent {
+    pub fn x0z(&self) -> Vec3 {
+        Vec3::new(self.movement.x, 0.0, self.movement.y)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.movement == Vec2::ZERO
+    }
+
+    pub fn consume(&mut self) {
+        self.jump.consume();
+        self.dash.consume();
+        self.crouch.consume();
+        self.sprint.consume();
+    }
+}
+
+fn latch_keys(button: &mut ButtonState, keyboard: &Input<KeyCode>, keys: &[KeyCode]) {
+    button.latch(
+        keyboard.any_pressed(keys.iter().copied()),
+        keyboard.any_just_pressed(keys.iter().copied()),
+        keyboard.any_just_released(keys.iter().copied()),
+    );
+}
+
+fn intent(
+    mut intent_q: Query<&mut MovementIntent, With<LocallyControlled>>,
+    keyboard: Res<Input<KeyCode>>,
+    input_movement: Res<InputMovement>,
+    replay: Option<Res<InputReplay>>,
+) {
+    if replay.is_some_and(|replay| replay.is_playing()) {
+        return;
+    }
+
+    for mut intent in &mut intent_q {
+        let mut local_intent = *intent;
+        local_intent.movement = input_movement.xy();
+        latch_keys(&mut local_intent.jump, &keyboard, &[KeyCode::Space]);
+        latch_keys(&mut local_intent.dash, &keyboard, &[KeyCode::F]);
+        latch_keys(
+            &mut local_intent.crouch,
+            &keyboard,
+            &[KeyCode::LControl, KeyCode::C],
+        );
+        latch_keys(
+            &mut local_intent.sprint,
+            &keyboard,
+            &[KeyCode::LShift, KeyCode::RShift],
+        );
+
+        if *intent != local_intent {
+            *intent = local_intent;
+        }
+    }
+}
+
+pub fn consume_intents(mut intent_q: Query<&mut MovementIntent>) {
+    for mut intent in &mut intent_q {
+        let mut consumed = *intent;
+        consumed.consume();
+
+        if *intent != consumed {
+            *intent = consumed;
+        }
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MovementBasis {
+    #[default]
+    World,
+    Camera,
+}
+
+#[derive(Component, Default, Clone)]
+pub struct InputDirection(pub(crate) Vec3);
+
+impl InputDirection {
+    pub fn get(&self) -> Vec3 {
+        self.0
+    }
+
+    pub fn set(&mut self, direction: Vec3) {
+        self.0 = direction;
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == Vec3::ZERO
+    }
+}
+
+fn direction(
+    mut direction_q: Query<(&mut InputDirection, &MovementBasis, &MovementIntent)>,
+    camera_q: Query<&GlobalTransform, With<CameraMain>>,
+) {
+    for (mut input_direction, basis, intent) in &mut direction_q {
+        let direction = match basis {
+            MovementBasis::World => intent.x0z(),
+            MovementBasis::Camera => match camera_q.get_single() {
+                Ok(camera) => {
+                    let forward = camera.forward().x0z().normalize_or_zero();
+                    let right = camera.right().x0z().normalize_or_zero();
+                    (right * intent.movement.x - forward * intent.movement.y).normalize_or_zero()
+                }
+                Err(_) => intent.x0z(),
+            },
+        };
+
+        if input_direction.0 != direction {
+            input_direction.0 = direction;
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_extensions::FromLookExt;
 
-use crate::{assets::*, Block, SpawnBlockExt};
+use crate::{assets::*, Block, BouncePad, SpawnBlockExt};
 
 pub struct LevelPlugin;
 
@@ -50,6 +50,58 @@ fn spawn_blocks(mut commands: Commands, assets: Res<MyAssets>) {
                 ..Default::default()
             },
         )
+        // Skate
+        .spawn_block(
+            &assets,
+            Block::Skate,
+            Transform {
+                translation: Vec3::X * 14.0,
+                scale: Vec3::new(8.0, 1.0, 16.0),
+                ..Default::default()
+            },
+        )
+        // Conveyor
+        .spawn_block(
+            &assets,
+            Block::Conveyor(Vec3::Z * 4.0),
+            Transform {
+                translation: Vec3::new(-12.0, 0.0, 0.0),
+                scale: Vec3::new(3.0, 1.0, 16.0),
+                ..Default::default()
+            },
+        )
+        // Bounce pads
+        .spawn_block(
+            &assets,
+            Block::BouncePad(BouncePad::default()),
+            Transform {
+                translation: Vec3::new(7.0, 0.25, 10.0),
+                scale: Vec3::new(2.0, 0.5, 2.0),
+                ..Default::default()
+            },
+        )
+        .spawn_block(
+            &assets,
+            Block::BouncePad(BouncePad {
+                height: 4.0,
+                direction: Vec3::new(-1.0, 1.0, 0.0),
+            }),
+            Transform {
+                translation: Vec3::new(-7.0, 0.25, 10.0),
+                scale: Vec3::new(2.0, 0.5, 2.0),
+                ..Default::default()
+            },
+        )
+        // Planetoid
+        .spawn_block(
+            &assets,
+            Block::Planetoid,
+            Transform {
+                translation: Vec3::new(0.0, 9.0, -24.0),
+                scale: Vec3::splat(6.0),
+                ..Default::default()
+            },
+        )
         // Cube
         .spawn_block(
             &assets,
@@ -90,6 +142,26 @@ fn spawn_blocks(mut commands: Commands, assets: Res<MyAssets>) {
                 ..Default::default()
             },
         )
+        // Ladder
+        .spawn_block(
+            &assets,
+            Block::Ladder,
+            Transform {
+                translation: Vec3::new(-5.25, 2.25, 4.0),
+                scale: Vec3::new(0.5, 4.5, 1.0),
+                ..Default::default()
+            },
+        )
+        // Water
+        .spawn_block(
+            &assets,
+            Block::Water,
+            Transform {
+                translation: Vec3::new(0.0, 1.5, 10.0),
+                scale: Vec3::new(8.0, 3.0, 6.0),
+                ..Default::default()
+            },
+        )
         // Spinner
         .spawn_block(
             &assets,
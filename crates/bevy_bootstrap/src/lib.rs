@@ -2,16 +2,34 @@ use bevy::prelude::*;
 
 mod actor;
 mod assets;
+mod blend;
 mod block;
 mod camera;
+mod dash;
+mod debug;
+mod gravity;
+mod headless;
 mod input;
 mod level;
+mod locomotion;
+mod replay;
+mod surface;
+mod tuning;
 
 pub use actor::*;
 pub use assets::*;
+pub use blend::*;
 pub use block::*;
 pub use camera::*;
+pub use dash::*;
+pub use debug::*;
+pub use gravity::*;
+pub use headless::*;
 pub use input::*;
+pub use locomotion::*;
+pub use replay::*;
+pub use surface::*;
+pub use tuning::*;
 
 pub struct BootstrapPlugin;
 
@@ -21,6 +39,13 @@ impl Plugin for BootstrapPlugin {
             .add_plugin(level::LevelPlugin)
             .add_plugin(camera::CameraPlugin)
             .add_plugin(input::InputPlugin)
+            .add_plugin(input::InputTickPlugin)
+            .add_plugin(replay::ReplayPlugin)
+            .add_plugin(locomotion::LocomotionPlugin)
+            .add_plugin(surface::SurfacePlugin)
+            .add_plugin(surface::ConveyorPlugin)
+            .add_plugin(gravity::GravityPlugin)
+            .add_plugin(debug::DebugDrawPlugin)
             .add_system_to_stage(CoreStage::PreUpdate, bevy::window::close_on_esc);
     }
 }
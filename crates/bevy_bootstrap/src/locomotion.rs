@@ -0,0 +1,255 @@
+use std::marker::PhantomData;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_extensions::Vec3UpExt;
+use bevy_physics::{PhysicsTick, SnapshotAppExt, Velocity};
+
+use crate::GravityField;
+
+pub struct LocomotionPlugin;
+
+impl Plugin for LocomotionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LocomotionEvent>()
+            .add_snapshot_component::<Locomotion>();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocomotionEvent {
+    Jumped(Entity),
+    LeftGround(Entity),
+    Landed {
+        entity: Entity,
+        fall_distance: f32,
+        impact_speed: f32,
+    },
+    StartedMoving(Entity),
+    Stopped(Entity),
+    EnteredWater(Entity),
+    GrabbedLedge(Entity),
+}
+
+// What the character is standing on, hanging from or floating in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Footing {
+    #[default]
+    Ground,
+    Air,
+    Ledge,
+    Ladder,
+    Water,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LocomotionState {
+    #[default]
+    Idle,
+    Walking,
+    Jumping,
+    Falling,
+    Landing,
+    Climbing,
+}
+
+pub struct LocomotionSample {
+    pub footing: Footing,
+    pub speed: f32,
+    pub vertical_speed: f32,
+    pub height: f32,
+}
+
+// Lets `update_locomotion` read the components of either physics backend
+pub trait LocomotionGround: Component {
+    fn footing(&self) -> Footing;
+}
+
+pub trait LocomotionVelocity: Component {
+    fn linear(&self) -> Vec3;
+}
+
+pub trait LocomotionTick: Resource {
+    fn delta(&self) -> f32;
+
+    // Only backends with rollback replay ticks
+    fn is_resimulating(&self) -> bool {
+        false
+    }
+}
+
+impl LocomotionVelocity for Velocity {
+    fn linear(&self) -> Vec3 {
+        self.linvel
+    }
+}
+
+impl LocomotionTick for PhysicsTick {
+    fn delta(&self) -> f32 {
+        PhysicsTick::delta(self)
+    }
+
+    fn is_resimulating(&self) -> bool {
+        PhysicsTick::is_resimulating(self)
+    }
+}
+
+// Rollback replays ticks whose transitions were already sent
+#[derive(SystemParam)]
+pub struct LocomotionEvents<'w, 's, T: LocomotionTick> {
+    events: EventWriter<'w, 's, LocomotionEvent>,
+    tick: Res<'w, T>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<T: LocomotionTick> LocomotionEvents<'_, '_, T> {
+    fn send(&mut self, event: LocomotionEvent) {
+        if !self.tick.is_resimulating() {
+            self.events.send(event);
+        }
+    }
+}
+
+const MOVING_SPEED: f32 = 0.5;
+const LANDING_TIME: f32 = 0.15;
+
+#[derive(Component, Clone)]
+pub struct Locomotion {
+    state: LocomotionState,
+    footing: Footing,
+    moving: bool,
+    jumped: bool,
+    jump_pending: bool,
+    peak_height: f32,
+    vertical_speed: f32,
+    landing: f32,
+}
+
+impl Default for Locomotion {
+    fn default() -> Self {
+        Self {
+            state: LocomotionState::Idle,
+            footing: Footing::Ground,
+            moving: false,
+            jumped: false,
+            jump_pending: false,
+            peak_height: 0.0,
+            vertical_speed: 0.0,
+            landing: 0.0,
+        }
+    }
+}
+
+impl Locomotion {
+    pub fn state(&self) -> LocomotionState {
+        self.state
+    }
+
+    // Supported by anything, not only the ground
+    pub fn is_grounded(&self) -> bool {
+        self.footing != Footing::Air
+    }
+
+    pub fn jump<T: LocomotionTick>(&mut self, entity: Entity, events: &mut LocomotionEvents<T>) {
+        self.jumped = true;
+        self.jump_pending = true;
+        self.state = LocomotionState::Jumping;
+        events.send(LocomotionEvent::Jumped(entity));
+    }
+
+    // Launchers start a fresh airborne phase, as if the character had never jumped
+    pub fn reset_jump(&mut self) {
+        self.jumped = false;
+        self.jump_pending = false;
+    }
+
+    pub fn update<T: LocomotionTick>(
+        &mut self,
+        entity: Entity,
+        sample: LocomotionSample,
+        delta: f32,
+        events: &mut LocomotionEvents<T>,
+    ) {
+        let moving = sample.speed > MOVING_SPEED;
+        if self.moving != moving {
+            self.moving = moving;
+            events.send(if moving {
+                LocomotionEvent::StartedMoving(entity)
+            } else {
+                LocomotionEvent::Stopped(entity)
+            });
+        }
+
+        if self.footing != sample.footing {
+            match sample.footing {
+                Footing::Air => events.send(LocomotionEvent::LeftGround(entity)),
+                Footing::Ground => {
+                    // The solver has already stopped the body, so the impact uses last tick's speed.
+                    events.send(LocomotionEvent::Landed {
+                        entity,
+                        fall_distance: (self.peak_height - sample.height).max(0.0),
+                        impact_speed: (-self.vertical_speed).max(0.0),
+                    });
+                    self.landing = LANDING_TIME;
+                }
+                Footing::Water => events.send(LocomotionEvent::EnteredWater(entity)),
+                Footing::Ledge => events.send(LocomotionEvent::GrabbedLedge(entity)),
+                Footing::Ladder => {}
+            }
+
+            if sample.footing != Footing::Air {
+                self.jumped = false;
+            }
+        }
+
+        // Falls are measured from the highest point since the last footing
+        self.peak_height = match sample.footing {
+            Footing::Air => self.peak_height.max(sample.height),
+            _ => sample.height,
+        };
+        self.footing = sample.footing;
+        self.vertical_speed = sample.vertical_speed;
+        self.landing = (self.landing - delta).max(0.0);
+
+        self.state = if self.jump_pending {
+            LocomotionState::Jumping
+        } else if self.footing == Footing::Ladder {
+            LocomotionState::Climbing
+        } else if self.footing == Footing::Air {
+            if self.jumped && sample.vertical_speed > 0.0 {
+                LocomotionState::Jumping
+            } else {
+                LocomotionState::Falling
+            }
+        } else if self.landing > 0.0 {
+            LocomotionState::Landing
+        } else if self.moving {
+            LocomotionState::Walking
+        } else {
+            LocomotionState::Idle
+        };
+
+        self.jump_pending = false;
+    }
+}
+
+pub fn update_locomotion<G: LocomotionGround, V: LocomotionVelocity, T: LocomotionTick>(
+    mut locomotion_q: Query<(Entity, &mut Locomotion, &G, &V, &GravityField, &Transform)>,
+    mut events: LocomotionEvents<T>,
+) {
+    let delta = events.tick.delta();
+    for (entity, mut locomotion, ground, velocity, gravity_field, transform) in &mut locomotion_q {
+        let up = gravity_field.up(transform.translation);
+        locomotion.update(
+            entity,
+            LocomotionSample {
+                footing: ground.footing(),
+                speed: velocity.linear().horizontal(up).length(),
+                vertical_speed: velocity.linear().vertical(up),
+                height: transform.translation.vertical(up),
+            },
+            delta,
+            &mut events,
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, LineWriter, Write},
+    str::FromStr,
+};
+
+use bevy::prelude::*;
+use bevy_physics::*;
+
+use crate::{ButtonState, InputDirection, LocallyControlled, MovementIntent};
+
+// Run with `--record <file>` to write every tick's input, `--replay <file>` to play
+// it back instead of the keyboard, or `--verify <file>` to also compare positions.
+// Each locally controlled character gets its own frames, numbered in spawn order.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputReplay::from_args())
+            .add_physics_system(PhysicsLabel::Input, replay);
+    }
+}
+
+#[derive(Resource, Default)]
+pub enum InputReplay {
+    #[default]
+    Off,
+    Recording(LineWriter<File>),
+    Playing {
+        frames: Vec<ReplayFrame>,
+        cursor: usize,
+        verify: bool,
+        diverged: bool,
+    },
+}
+
+impl InputReplay {
+    pub fn record(path: &str) -> io::Result<Self> {
+        let mut writer = LineWriter::new(File::create(path)?);
+        writeln!(writer, "{}", ReplayFrame::HEADER)?;
+        Ok(Self::Recording(writer))
+    }
+
+    pub fn play(path: &str, verify: bool) -> io::Result<Self> {
+        let mut frames = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+
+            let frame = line.parse::<ReplayFrame>().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("bad frame: {line}"))
+            })?;
+            frames.push(frame);
+        }
+
+        Ok(Self::Playing {
+            frames,
+            cursor: 0,
+            verify,
+            diverged: false,
+        })
+    }
+
+    // The flags can sit anywhere among the other arguments; the first one found
+    // in the order record, replay, verify wins
+    fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        let path = |flag: &str| {
+            args.iter()
+                .position(|arg| arg == flag)
+                .map(|index| args.get(index + 1))
+        };
+
+        let replay = match (path("--record"), path("--replay"), path("--verify")) {
+            (Some(Some(path)), ..) => Self::record(path),
+            (_, Some(Some(path)), _) => Self::play(path, false),
+            (.., Some(Some(path))) => Self::play(path, true),
+            (None, None, None) => Ok(Self::Off),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--record, --replay and --verify expect a file",
+            )),
+        };
+
+        replay.unwrap_or_else(|err| {
+            error!("input replay disabled: {err}");
+            Self::Off
+        })
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self, Self::Playing { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayFrame {
+    pub tick: u64,
+    pub player: usize,
+    pub intent: MovementIntent,
+    pub direction: Vec3,
+    pub position: Vec3,
+}
+
+impl ReplayFrame {
+    const HEADER: &'static str = "# tick player movement.x movement.y jump dash crouch sprint \
+        direction.x direction.y direction.z position.x position.y position.z";
+}
+
+// Floats are written with their shortest round-trip representation, so a replay
+// reads back the exact bits that were recorded.
+impl fmt::Display for ReplayFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {} {} {} {} {} {} {}",
+            self.tick,
+            self.player,
+            self.intent.movement.x,
+            self.intent.movement.y,
+            self.intent.jump.bits(),
+            self.intent.dash.bits(),
+            self.intent.crouch.bits(),
+            self.intent.sprint.bits(),
+            self.direction.x,
+            self.direction.y,
+            self.direction.z,
+            self.position.x,
+            self.position.y,
+            self.position.z,
+        )
+    }
+}
+
+impl FromStr for ReplayFrame {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 14 {
+            return Err(());
+        }
+
+        let float = |index: usize| fields[index].parse::<f32>().map_err(|_| ());
+        let button = |index: usize| {
+            fields[index]
+                .parse::<u8>()
+                .map(ButtonState::from_bits)
+                .map_err(|_| ())
+        };
+
+        Ok(Self {
+            tick: fields[0].parse().map_err(|_| ())?,
+            player: fields[1].parse().map_err(|_| ())?,
+            intent: MovementIntent {
+                movement: Vec2::new(float(2)?, float(3)?),
+                jump: button(4)?,
+                dash: button(5)?,
+                crouch: button(6)?,
+                sprint: button(7)?,
+            },
+            direction: Vec3::new(float(8)?, float(9)?, float(10)?),
+            position: Vec3::new(float(11)?, float(12)?, float(13)?),
+        })
+    }
+}
+
+fn replay(
+    mut replay: ResMut<InputReplay>,
+    mut player_q: Query<
+        (Entity, &mut MovementIntent, &mut InputDirection, &Transform),
+        With<LocallyControlled>,
+    >,
+    tick: Res<PhysicsTick>,
+) {
+    // Entities are spawned in the same order every run, so sorting by entity gives
+    // each player the same number in the recording and the replay
+    let mut players: Vec<_> = player_q.iter_mut().collect();
+    players.sort_by_key(|(entity, ..)| *entity);
+
+    match &mut *replay {
+        InputReplay::Off => {}
+        InputReplay::Recording(writer) => {
+            for (player, (_, intent, direction, transform)) in players.iter().enumerate() {
+                let frame = ReplayFrame {
+                    tick: tick.count(),
+                    player,
+                    intent: **intent,
+                    direction: direction.0,
+                    position: transform.translation,
+                };
+
+                if let Err(err) = writeln!(writer, "{frame}") {
+                    error!("input recording stopped: {err}");
+                    *replay = InputReplay::Off;
+                    return;
+                }
+            }
+        }
+        InputReplay::Playing {
+            frames,
+            cursor,
+            verify,
+            diverged,
+        } => {
+            while frames
+                .get(*cursor)
+                .is_some_and(|frame| frame.tick < tick.count())
+            {
+                *cursor += 1;
+            }
+
+            while let Some(frame) = frames
+                .get(*cursor)
+                .filter(|frame| frame.tick == tick.count())
+            {
+                let (_, intent, direction, transform) = match players.get_mut(frame.player) {
+                    Some(player) => player,
+                    None => {
+                        error!(
+                            "replay stopped at tick {}: recorded player {} but only {} are \
+                            locally controlled",
+                            frame.tick,
+                            frame.player,
+                            players.len()
+                        );
+                        *replay = InputReplay::Off;
+                        return;
+                    }
+                };
+
+                **intent = frame.intent;
+                direction.0 = frame.direction;
+
+                if *verify && !*diverged && frame.position != transform.translation {
+                    error!(
+                        "replay diverged at tick {} for player {}: recorded {}, replayed {}",
+                        frame.tick, frame.player, frame.position, transform.translation
+                    );
+                    *diverged = true;
+                }
+
+                *cursor += 1;
+            }
+
+            if *cursor >= frames.len() {
+                if *verify && !*diverged {
+                    info!("replay verified through tick {}", tick.count());
+                } else {
+                    info!("replay finished at tick {}", tick.count());
+                }
+                *replay = InputReplay::Off;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_round_trips_exact_bits() {
+        let mut jump = ButtonState::default();
+        jump.latch(true, true, false);
+        let mut crouch = ButtonState::default();
+        crouch.latch(false, true, true);
+
+        let frame = ReplayFrame {
+            tick: 1234,
+            player: 1,
+            intent: MovementIntent {
+                movement: Vec2::new(-0.70710677, 0.1),
+                jump,
+                crouch,
+                ..Default::default()
+            },
+            direction: Vec3::new(1.0 / 3.0, 0.0, -2.0 / 3.0),
+            position: Vec3::new(f32::MIN_POSITIVE, 1e-7, -123456.79),
+        };
+
+        let parsed: ReplayFrame = frame.to_string().parse().unwrap();
+        assert_eq!(parsed, frame);
+        assert_eq!(
+            parsed.position.to_array().map(f32::to_bits),
+            frame.position.to_array().map(f32::to_bits)
+        );
+    }
+
+    #[test]
+    fn rejects_frames_with_missing_fields() {
+        assert!("1 0 0.0 0.0 0 0 0 0".parse::<ReplayFrame>().is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_extensions::*;
+use bevy_physics::*;
+use serde::Deserialize;
+
+use crate::{GravityField, Scalars};
+
+pub struct SurfacePlugin;
+
+impl Plugin for SurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SurfaceMaterial>()
+            .register_type::<MovementMode>();
+    }
+}
+
+pub struct ConveyorPlugin;
+
+impl Plugin for ConveyorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(PhysicsLabel::Update, convey);
+    }
+}
+
+#[derive(Component)]
+pub struct GroundProbe;
+
+// Fields left out of a tuning file keep their default; the conveyor velocity
+// belongs to the block rather than the tuning
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Deserialize)]
+#[reflect(Component)]
+#[serde(default)]
+pub struct SurfaceMaterial {
+    pub speed: f32,
+    pub acceleration: f32,
+    pub damping: f32,
+    pub gravity: f32,
+    pub jump_height: f32,
+    pub mode: MovementMode,
+    #[serde(skip)]
+    pub velocity: Vec3,
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            acceleration: 1.0,
+            damping: 1.0,
+            gravity: 1.0,
+            jump_height: 1.0,
+            mode: MovementMode::Free,
+            velocity: Vec3::ZERO,
+        }
+    }
+}
+
+impl SurfaceMaterial {
+    pub fn scalars(&self) -> Scalars {
+        Scalars {
+            speed: self.speed,
+            acceleration: self.acceleration,
+            damping: self.damping,
+            gravity: self.gravity,
+            jump_height: self.jump_height,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.scalars().validate()?;
+        match self.mode {
+            MovementMode::Free => Ok(()),
+            // An infinite turn rate snaps the heading to the input
+            MovementMode::Forward { turn_rate } if turn_rate >= 0.0 => Ok(()),
+            MovementMode::Forward { turn_rate } => Err(format!(
+                "mode.turn_rate must be a non-negative number, got {turn_rate}"
+            )),
+        }
+    }
+}
+
+// Names a tuned material for the block it's on; blocks without one use the
+// material they were spawned with
+#[derive(Component, Deref, Debug, Clone, PartialEq, Eq)]
+pub struct SurfaceName(pub String);
+
+impl From<&str> for SurfaceName {
+    fn from(name: &str) -> Self {
+        Self(name.to_string())
+    }
+}
+
+// Tuned materials, looked up by the names blocks and tiles refer to
+#[derive(Deref, Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Surfaces(HashMap<String, SurfaceMaterial>);
+
+impl<const N: usize> From<[(&str, SurfaceMaterial); N]> for Surfaces {
+    fn from(surfaces: [(&str, SurfaceMaterial); N]) -> Self {
+        Self(
+            surfaces
+                .into_iter()
+                .map(|(name, surface)| (name.to_string(), surface))
+                .collect(),
+        )
+    }
+}
+
+impl Surfaces {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, surface) in &self.0 {
+            surface.validate().map_err(|err| format!("{name}.{err}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+pub enum MovementMode {
+    #[default]
+    Free,
+    Forward {
+        turn_rate: f32,
+    },
+}
+
+const CONVEYOR_GRIP: f32 = 20.0;
+
+fn convey(
+    surface_q: Query<(Entity, &SurfaceMaterial)>,
+    mut body_q: Query<
+        (&mut Velocity, &RigidBody, &Transform, Option<&GravityField>),
+        Without<GroundProbe>,
+    >,
+    physics: Res<PhysicsContext>,
+    tick: Res<PhysicsTick>,
+) {
+    for (entity, surface) in &surface_q {
+        if surface.velocity == Vec3::ZERO {
+            continue;
+        }
+
+        for contact in physics.contacts_with(entity) {
+            if !contact.has_any_active_contacts() {
+                continue;
+            }
+
+            let other = if contact.collider1() == entity {
+                contact.collider2()
+            } else {
+                contact.collider1()
+            };
+
+            if let Ok((mut velocity, RigidBody::Dynamic, transform, gravity_field)) =
+                body_q.get_mut(other)
+            {
+                // Bodies without a field of their own fall along -Y
+                let up = gravity_field
+                    .copied()
+                    .unwrap_or_default()
+                    .up(transform.translation);
+                velocity.linvel = velocity
+                    .linvel
+                    .horizontal(up)
+                    .move_towards(
+                        surface.velocity.horizontal(up),
+                        CONVEYOR_GRIP * tick.delta(),
+                    )
+                    .with_vertical(up, velocity.linvel.vertical(up));
+            }
+        }
+    }
+}
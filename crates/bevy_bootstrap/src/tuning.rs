@@ -0,0 +1,123 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadState, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::de::DeserializeOwned;
+
+// Tuning values read from a RON asset. The resource starts out as `Default` and is
+// replaced whenever the asset loads or changes on disk and passes `validate`.
+pub trait Tuning: Resource + TypeUuid + DeserializeOwned + Clone + Default {
+    // Matched against the end of the file name, e.g. `tuning.ron`
+    const EXTENSION: &'static str;
+
+    fn validate(&self) -> Result<(), String>;
+}
+
+pub struct TuningPlugin<T> {
+    path: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<T> TuningPlugin<T> {
+    pub fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Tuning> Plugin for TuningPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<T>()
+            .add_asset::<T>()
+            .add_asset_loader(RonLoader::<T>(PhantomData));
+
+        let handle: Handle<T> = app.world.resource::<AssetServer>().load(self.path);
+        app.insert_resource(TuningHandle {
+            handle,
+            path: self.path,
+        })
+        .add_system_to_stage(CoreStage::PreUpdate, apply_tuning::<T>);
+    }
+}
+
+#[derive(Resource)]
+pub struct TuningHandle<T: Tuning> {
+    handle: Handle<T>,
+    path: &'static str,
+}
+
+impl<T: Tuning> TuningHandle<T> {
+    // Loaded or failed, so the resource won't change underneath a caller anymore
+    pub fn is_settled(&self, asset_server: &AssetServer) -> bool {
+        !matches!(
+            asset_server.get_load_state(&self.handle),
+            LoadState::NotLoaded | LoadState::Loading
+        )
+    }
+}
+
+pub fn non_negative(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "{name} must be a finite non-negative number, got {value}"
+        ))
+    }
+}
+
+struct RonLoader<T>(PhantomData<T>);
+
+impl<T: Tuning> AssetLoader for RonLoader<T> {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let tuning = ron::de::from_bytes::<T>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(tuning));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[T::EXTENSION]
+    }
+}
+
+// Parse errors are reported by the asset server; invalid values are reported here.
+// Either way the last good tuning stays in place.
+fn apply_tuning<T: Tuning>(
+    mut events: EventReader<AssetEvent<T>>,
+    assets: Res<Assets<T>>,
+    handle: Res<TuningHandle<T>>,
+    mut tuning: ResMut<T>,
+) {
+    for event in events.iter() {
+        let changed = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue,
+        };
+
+        if *changed != handle.handle {
+            continue;
+        }
+
+        if let Some(loaded) = assets.get(changed) {
+            match loaded.validate() {
+                Ok(()) => {
+                    *tuning = loaded.clone();
+                    info!("applied tuning from {}", handle.path);
+                }
+                Err(err) => error!("ignored invalid tuning in {}: {err}", handle.path),
+            }
+        }
+    }
+}
@@ -76,3 +76,23 @@ impl Vec3SwizzlesExt for Vec3 {
         Self::new(self.x, y, self.z)
     }
 }
+
+pub trait Vec3UpExt {
+    fn horizontal(self, up: Vec3) -> Vec3;
+    fn vertical(self, up: Vec3) -> f32;
+    fn with_vertical(self, up: Vec3, vertical: f32) -> Vec3;
+}
+
+impl Vec3UpExt for Vec3 {
+    fn horizontal(self, up: Vec3) -> Vec3 {
+        self - up * self.dot(up)
+    }
+
+    fn vertical(self, up: Vec3) -> f32 {
+        self.dot(up)
+    }
+
+    fn with_vertical(self, up: Vec3, vertical: f32) -> Vec3 {
+        self.horizontal(up) + up * vertical
+    }
+}
@@ -121,8 +121,9 @@ impl Mul<CellInt> for SquareCell {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub enum SquareDirection {
+    #[default]
     North = 0,
     NorthEast = 1,
     East = 2,
@@ -133,12 +134,6 @@ pub enum SquareDirection {
     NorthWest = 7,
 }
 
-impl Default for SquareDirection {
-    fn default() -> Self {
-        Self::North
-    }
-}
-
 impl From<SquareDirection> for IVec3 {
     fn from(dir: SquareDirection) -> Self {
         match dir {
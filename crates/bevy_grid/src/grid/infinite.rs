@@ -25,11 +25,15 @@ where
         }
     }
 
+    pub fn cell_size(&self) -> CellFloat {
+        self.cell_size
+    }
+
     pub fn get_tile_from_point(&self, point: CellPointFloat) -> Option<&T> {
         self.get_tile(self.get_cell(point))
     }
 
-    pub fn iter(&self) -> bevy::utils::hashbrown::hash_map::Iter<C, T> {
+    pub fn iter(&self) -> bevy::utils::hashbrown::hash_map::Iter<'_, C, T> {
         self.tiles.iter()
     }
 }
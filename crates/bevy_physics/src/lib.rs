@@ -2,18 +2,21 @@ use bevy::{ecs::schedule::ShouldRun, prelude::*};
 use bevy_rapier3d::prelude::*;
 
 pub use bevy_rapier3d::prelude::{
-    Ccd, CoefficientCombineRule, Collider, CollisionGroups, Damping, ExternalForce,
-    ExternalImpulse, Friction, GravityScale, Group, InteractionGroups,
-    KinematicCharacterController, LockedAxes, QueryFilter, RapierContext as PhysicsContext,
-    Restitution, RigidBody, Sleeping, Velocity,
+    Ccd, CoefficientCombineRule, Collider, CollisionGroups, Damping,
+    DebugRenderContext as PhysicsDebugRender, ExternalForce, ExternalImpulse, Friction,
+    GravityScale, Group, InteractionGroups, KinematicCharacterController, LockedAxes, QueryFilter,
+    QueryFilterFlags, RapierConfiguration as PhysicsConfiguration, RapierContext as PhysicsContext,
+    Restitution, RigidBody, Sensor, Sleeping, Velocity,
 };
 
 mod interpolation;
 mod layer;
+mod snapshot;
 mod tick;
 
 pub use interpolation::*;
 pub use layer::*;
+pub use snapshot::*;
 pub use tick::*;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
@@ -21,6 +24,7 @@ struct PhysicsStage;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum PhysicsLabel {
+    Input,
     PreUpdate,
     Update,
     PostUpdate,
@@ -50,9 +54,15 @@ impl Plugin for PhysicsPlugin {
             PhysicsStage,
             SystemStage::parallel()
                 .with_run_criteria(tick_run_criteria)
+                .with_system_set(
+                    SystemSet::new()
+                        .label(PhysicsLabel::Input)
+                        .with_system(|| {}),
+                )
                 .with_system_set(
                     SystemSet::new()
                         .label(PhysicsLabel::PreUpdate)
+                        .after(PhysicsLabel::Input)
                         .with_system(|| {}),
                 )
                 .with_system_set(
@@ -91,10 +101,16 @@ impl Plugin for PhysicsPlugin {
             ),
         )
         .add_plugin(TickPlugin)
-        .add_plugin(InterpolationPlugin);
+        .add_plugin(InterpolationPlugin)
+        .add_plugin(SnapshotPlugin);
 
-        #[cfg(debug_assertions)]
-        app.add_plugin(RapierDebugRenderPlugin::default());
+        // Starts hidden; toggled at runtime through `PhysicsDebugRender::enabled`
+        if app.is_plugin_added::<bevy::render::RenderPlugin>() {
+            app.add_plugin(RapierDebugRenderPlugin {
+                enabled: false,
+                ..Default::default()
+            });
+        }
     }
 }
 
@@ -115,9 +131,15 @@ impl PhysicsAppExt for App {
         system: impl IntoSystemDescriptor<Params>,
     ) -> &mut Self {
         match label {
-            PhysicsLabel::PreUpdate => {
-                self.add_system_to_stage(PhysicsStage, system.before(PhysicsLabel::PreUpdate))
+            PhysicsLabel::Input => {
+                self.add_system_to_stage(PhysicsStage, system.before(PhysicsLabel::Input))
             }
+            PhysicsLabel::PreUpdate => self.add_system_to_stage(
+                PhysicsStage,
+                system
+                    .before(PhysicsLabel::PreUpdate)
+                    .after(PhysicsLabel::Input),
+            ),
             PhysicsLabel::Update => self.add_system_to_stage(
                 PhysicsStage,
                 system
@@ -135,8 +157,15 @@ impl PhysicsAppExt for App {
 
     fn add_physics_system_set(&mut self, label: PhysicsLabel, system_set: SystemSet) -> &mut Self {
         match label {
-            PhysicsLabel::PreUpdate => self
-                .add_system_set_to_stage(PhysicsStage, system_set.before(PhysicsLabel::PreUpdate)),
+            PhysicsLabel::Input => {
+                self.add_system_set_to_stage(PhysicsStage, system_set.before(PhysicsLabel::Input))
+            }
+            PhysicsLabel::PreUpdate => self.add_system_set_to_stage(
+                PhysicsStage,
+                system_set
+                    .before(PhysicsLabel::PreUpdate)
+                    .after(PhysicsLabel::Input),
+            ),
             PhysicsLabel::Update => self.add_system_set_to_stage(
                 PhysicsStage,
                 system_set
@@ -0,0 +1,194 @@
+use std::any::Any;
+
+use bevy::{ecs::system::System, prelude::*};
+use bevy_rapier3d::plugin::systems::writeback_rigid_bodies;
+use bevy_rapier3d::rapier::prelude::{
+    BroadPhase, CCDSolver, ColliderSet, ImpulseJointSet, IslandManager, MultibodyJointSet,
+    NarrowPhase, QueryPipeline, RigidBodySet,
+};
+
+use super::*;
+
+pub(super) struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_snapshot_resource::<PhysicsTick>()
+            .add_snapshot_component::<Transform>()
+            .add_snapshot_component::<GlobalTransform>()
+            .add_snapshot_component::<Velocity>()
+            .add_snapshot_component::<ExternalImpulse>()
+            .add_snapshot_component::<ExternalForce>()
+            .add_snapshot_component::<Damping>()
+            .add_snapshot_component::<GravityScale>()
+            .add_snapshot_component::<Collider>();
+    }
+}
+
+pub type SnapshotData = Box<dyn Any + Send + Sync>;
+pub type CaptureFn = fn(&mut World) -> SnapshotData;
+pub type RestoreFn = fn(&mut World, &SnapshotData);
+
+#[derive(Resource, Default)]
+pub struct SnapshotRegistry(Vec<(CaptureFn, RestoreFn)>);
+
+pub trait SnapshotAppExt {
+    fn add_snapshot_component<T: Component + Clone>(&mut self) -> &mut Self;
+
+    fn add_snapshot_resource<T: Resource + Clone>(&mut self) -> &mut Self;
+
+    // For state that can't be cloned as a component or resource, e.g. something
+    // that has to be rebuilt on restore. Runs in registration order.
+    fn add_snapshot(&mut self, capture: CaptureFn, restore: RestoreFn) -> &mut Self;
+}
+
+impl SnapshotAppExt for App {
+    fn add_snapshot_component<T: Component + Clone>(&mut self) -> &mut Self {
+        self.add_snapshot(capture_component::<T>, restore_component::<T>)
+    }
+
+    fn add_snapshot_resource<T: Resource + Clone>(&mut self) -> &mut Self {
+        self.add_snapshot(capture_resource::<T>, restore_resource::<T>)
+    }
+
+    fn add_snapshot(&mut self, capture: CaptureFn, restore: RestoreFn) -> &mut Self {
+        self.init_resource::<SnapshotRegistry>();
+        self.world
+            .resource_mut::<SnapshotRegistry>()
+            .0
+            .push((capture, restore));
+        self
+    }
+}
+
+// Records every entity alive at the capture, including those without the
+// component, so the restore knows which of them gained it since
+fn capture_component<T: Component + Clone>(world: &mut World) -> SnapshotData {
+    let mut query = world.query::<(Entity, Option<&T>)>();
+    let components: Vec<(Entity, Option<T>)> = query
+        .iter(world)
+        .map(|(entity, component)| (entity, component.cloned()))
+        .collect();
+    Box::new(components)
+}
+
+// Only entities alive at the capture are rolled back; entities spawned since are
+// left untouched and despawned ones are not brought back.
+fn restore_component<T: Component + Clone>(world: &mut World, data: &SnapshotData) {
+    let components = match data.downcast_ref::<Vec<(Entity, Option<T>)>>() {
+        Some(components) => components,
+        None => return,
+    };
+
+    for (entity, component) in components {
+        let mut entity = match world.get_entity_mut(*entity) {
+            Some(entity) => entity,
+            None => continue,
+        };
+
+        match (entity.get_mut::<T>(), component) {
+            (Some(mut current), Some(component)) => *current = component.clone(),
+            (None, Some(component)) => {
+                entity.insert(component.clone());
+            }
+            (Some(_), None) => {
+                entity.remove::<T>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn capture_resource<T: Resource + Clone>(world: &mut World) -> SnapshotData {
+    Box::new(world.get_resource::<T>().cloned())
+}
+
+fn restore_resource<T: Resource + Clone>(world: &mut World, data: &SnapshotData) {
+    if let Some(Some(resource)) = data.downcast_ref::<Option<T>>() {
+        world.insert_resource(resource.clone());
+    }
+}
+
+struct RapierState {
+    islands: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    impulse_joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    ccd_solver: CCDSolver,
+    query_pipeline: QueryPipeline,
+}
+
+impl RapierState {
+    fn capture(context: &RapierContext) -> Self {
+        Self {
+            islands: context.islands.clone(),
+            broad_phase: context.broad_phase.clone(),
+            narrow_phase: context.narrow_phase.clone(),
+            bodies: context.bodies.clone(),
+            colliders: context.colliders.clone(),
+            impulse_joints: context.impulse_joints.clone(),
+            multibody_joints: context.multibody_joints.clone(),
+            ccd_solver: context.ccd_solver.clone(),
+            query_pipeline: context.query_pipeline.clone(),
+        }
+    }
+
+    fn restore(&self, context: &mut RapierContext) {
+        context.islands = self.islands.clone();
+        context.broad_phase = self.broad_phase.clone();
+        context.narrow_phase = self.narrow_phase.clone();
+        context.bodies = self.bodies.clone();
+        context.colliders = self.colliders.clone();
+        context.impulse_joints = self.impulse_joints.clone();
+        context.multibody_joints = self.multibody_joints.clone();
+        context.ccd_solver = self.ccd_solver.clone();
+        context.query_pipeline = self.query_pipeline.clone();
+    }
+}
+
+// Full simulation state: the rapier world plus every component and resource
+// registered with `SnapshotAppExt`.
+pub struct PhysicsSnapshot {
+    tick: u64,
+    rapier: RapierState,
+    data: Vec<SnapshotData>,
+}
+
+impl PhysicsSnapshot {
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn capture(world: &mut World) -> Self {
+        let registry = world.resource::<SnapshotRegistry>().0.clone();
+
+        Self {
+            tick: world.resource::<PhysicsTick>().count(),
+            rapier: RapierState::capture(world.resource::<RapierContext>()),
+            data: registry.iter().map(|(capture, _)| capture(world)).collect(),
+        }
+    }
+
+    pub fn restore(&self, world: &mut World) {
+        let registry = world.resource::<SnapshotRegistry>().0.clone();
+
+        self.rapier
+            .restore(&mut world.resource_mut::<RapierContext>());
+
+        // Rapier only re-reads a body's transform when it differs from the last
+        // one it wrote itself, and that bookkeeping isn't part of the cloned
+        // state. Writing the restored bodies back resets it, so bodies the
+        // original run left alone aren't re-read from a decomposed
+        // `GlobalTransform`, which isn't bit-exact for rotations.
+        let mut writeback = IntoSystem::into_system(writeback_rigid_bodies);
+        writeback.initialize(world);
+        writeback.run((), world);
+
+        for ((_, restore), data) in registry.iter().zip(&self.data) {
+            restore(world, data);
+        }
+    }
+}
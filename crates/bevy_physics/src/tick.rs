@@ -13,10 +13,13 @@ impl Plugin for TickPlugin {
 const PHYSICS_TICK_RATE: f64 = 20.0;
 pub(super) const PHYSICS_DELTA: f64 = 1.0 / PHYSICS_TICK_RATE;
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone)]
 pub struct PhysicsTick {
     accumulator: f64,
     looping: bool,
+    count: u64,
+    resimulate: u64,
+    resimulating: bool,
 }
 
 impl PhysicsTick {
@@ -28,17 +31,43 @@ impl PhysicsTick {
         PHYSICS_DELTA as f32
     }
 
+    // Number of ticks run so far, including the one in progress
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
     pub fn percent(&self) -> f32 {
         (self.accumulator / PHYSICS_DELTA) as f32
     }
 
+    // Steps the count back so the last `ticks` ticks run again before any new
+    // ones, e.g. after restoring a snapshot for rollback
+    pub fn rewind(&mut self, ticks: u64) {
+        let ticks = ticks.min(self.count);
+        self.count -= ticks;
+        self.resimulate += ticks;
+    }
+
+    pub fn is_resimulating(&self) -> bool {
+        self.resimulating
+    }
+
     fn update(&mut self, time: &Time) -> ShouldRun {
         if !self.looping {
             self.accumulator += time.delta_seconds_f64();
         }
 
+        self.resimulating = self.resimulate > 0;
+        if self.resimulating {
+            self.resimulate -= 1;
+            self.count += 1;
+            self.looping = true;
+            return ShouldRun::YesAndCheckAgain;
+        }
+
         if self.accumulator >= PHYSICS_DELTA {
             self.accumulator -= PHYSICS_DELTA;
+            self.count += 1;
             if self.accumulator >= PHYSICS_DELTA {
                 self.looping = true;
                 ShouldRun::YesAndCheckAgain
@@ -29,10 +29,14 @@ fn setup(mut commands: Commands) {
     let player = commands
         .spawn((
             Player,
+            LocallyControlled,
+            MovementIntent::default(),
+            MovementBasis::Camera,
+            InputDirection::default(),
             TransformBundle::default(),
             KinematicCharacterController::default(),
             RigidBody::KinematicPositionBased,
-            Collider::capsule((Vec3::Y * 0.5).into(), (Vec3::Y * 1.5).into(), 0.5),
+            Collider::capsule(Vec3::Y * 0.5, Vec3::Y * 1.5, 0.5),
         ))
         .id();
 
@@ -54,18 +58,17 @@ const MAX_SPEED: f32 = 10.0;
 // const JUMP_HEIGHT: f32 = 2.0;
 
 fn movement(
-    mut player_q: Query<&mut KinematicCharacterController, With<Player>>,
-    input: Res<InputMovement>,
+    mut player_q: Query<(&mut KinematicCharacterController, &InputDirection), With<Player>>,
     time: Res<Time>,
 ) {
-    let mut controller = player_q.single_mut();
+    for (mut controller, input_direction) in &mut player_q {
+        let input = input_direction.get();
+        let dt = time.delta_seconds();
+        let target = input * MAX_SPEED;
+        // let max_delta = MAX_ACCELERATION * dt;
 
-    let input = input.x0z();
-    let dt = time.delta_seconds();
-    let target = input * MAX_SPEED;
-    // let max_delta = MAX_ACCELERATION * dt;
-
-    controller.translation = Some(target * dt);
+        controller.translation = Some(target * dt);
+    }
 
     // let v = velocity
     //     .linvel
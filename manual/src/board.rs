@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use bevy_bootstrap::{MaterialName, MeshName, MyAssets};
+use bevy_bootstrap::{MaterialName, MeshName, MyAssets, SurfaceMaterial, Surfaces};
 use bevy_grid::*;
 
 pub struct BoardPlugin;
@@ -17,7 +17,7 @@ impl Plugin for BoardPlugin {
 }
 
 #[derive(Resource, Deref, DerefMut)]
-pub struct Platforms(GridInfinite<SquareCell, Platform>);
+pub struct Platforms(GridInfinite<SquareCell, Tile>);
 
 impl Default for Platforms {
     fn default() -> Self {
@@ -25,7 +25,36 @@ impl Default for Platforms {
     }
 }
 
-#[derive(Default)]
+impl Platforms {
+    // Cells overlapped by the horizontal extent of a box; touching edges don't count
+    pub fn cells_in(&self, min: Vec3, max: Vec3) -> impl Iterator<Item = SquareCell> {
+        let size = self.cell_size();
+        let (col_min, col_max) = ((min.x / size).floor() as i32, (max.x / size).ceil() as i32);
+        let (row_min, row_max) = ((min.z / size).floor() as i32, (max.z / size).ceil() as i32);
+
+        (col_min..col_max)
+            .flat_map(move |col| (row_min..row_max).map(move |row| SquareCell::new(col, row)))
+    }
+
+    // Any tile in the box whose top is above `feet`
+    pub fn is_blocked(&self, min: Vec3, max: Vec3, feet: f32) -> bool {
+        self.cells_in(min, max)
+            .filter_map(|cell| self.get_tile(cell))
+            .any(|tile| tile.height > feet)
+    }
+
+    // Highest tile top in the box that is at or below `feet`. Cells without a tile
+    // have no floor at all.
+    pub fn floor_height(&self, min: Vec3, max: Vec3, feet: f32) -> Option<f32> {
+        self.cells_in(min, max)
+            .filter_map(|cell| self.get_tile(cell))
+            .map(|tile| tile.height)
+            .filter(|height| *height <= feet)
+            .reduce(f32::max)
+    }
+}
+
+#[derive(Default, Clone, Copy)]
 pub enum Platform {
     #[default]
     Ground,
@@ -33,7 +62,49 @@ pub enum Platform {
     Skate,
 }
 
-impl<C> GridTile<C> for Platform
+impl Platform {
+    pub const ALL: [Platform; 3] = [Platform::Ground, Platform::Ice, Platform::Skate];
+
+    // Plain ground is the default material
+    pub fn surface_name(&self) -> Option<&'static str> {
+        match self {
+            Platform::Ground => None,
+            Platform::Ice => Some("ice"),
+            Platform::Skate => Some("skate"),
+        }
+    }
+
+    pub fn surface(&self, surfaces: &Surfaces) -> SurfaceMaterial {
+        self.surface_name()
+            .and_then(|name| surfaces.get(name))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+// A solid column of the board reaching up to `height`
+#[derive(Default)]
+pub struct Tile {
+    pub platform: Platform,
+    pub height: f32,
+}
+
+impl Tile {
+    fn with_height(self, height: f32) -> Self {
+        Self { height, ..self }
+    }
+}
+
+impl From<Platform> for Tile {
+    fn from(platform: Platform) -> Self {
+        Self {
+            platform,
+            height: 0.0,
+        }
+    }
+}
+
+impl<C> GridTile<C> for Tile
 where
     C: GridCell,
 {
@@ -48,35 +119,62 @@ where
     }
 }
 
-fn set_platforms(mut platforms: ResMut<Platforms>) {
-    platforms.set_tile(SquareCell::ZERO, Platform::Ground);
+const BOARD_SIZE: i32 = 32;
+const WALL_HEIGHT: f32 = 3.0;
+const PIT_DEPTH: f32 = 1.5;
+// Bottom of the rendered tile columns, below the deepest pit
+const TILE_BASE: f32 = -2.0;
 
-    for cell in CellDirectionIter::new(SquareCell::ZERO, 1) {
-        platforms.set_tile(cell, Platform::Ground);
+fn set_platforms(mut platforms: ResMut<Platforms>) {
+    // Ground walled in on all sides
+    let half = BOARD_SIZE / 2;
+    let size = BOARD_SIZE as u32;
+    for cell in CellBoxIter::new(SquareCell::new(-half, -half), size, size) {
+        let edge = [cell.column(), cell.row()]
+            .into_iter()
+            .any(|index| index == -half || index == half - 1);
+        let height = if edge { WALL_HEIGHT } else { 0.0 };
+        platforms.set_tile(cell, Tile::from(Platform::Ground).with_height(height));
     }
 
     for cell in CellBoxIter::new(SquareCell::new(-8, -8), 16, 4) {
-        platforms.set_tile(cell, Platform::Ice);
+        platforms.set_tile(cell, Platform::Ice.into());
     }
 
     for cell in CellBoxIter::new(SquareCell::new(-8, 8), 16, 4) {
-        platforms.set_tile(cell, Platform::Skate);
+        platforms.set_tile(cell, Platform::Skate.into());
+    }
+
+    // Steps up to a raised platform
+    for (index, height) in [0.5, 1.0, 1.5].into_iter().enumerate() {
+        for cell in CellBoxIter::new(SquareCell::new(10 + index as i32, -1), 1, 3) {
+            platforms.set_tile(cell, Tile::from(Platform::Ground).with_height(height));
+        }
+    }
+
+    // A pit to fall into and jump back out of
+    for cell in CellBoxIter::new(SquareCell::new(-12, -1), 3, 3) {
+        platforms.set_tile(cell, Tile::from(Platform::Ground).with_height(-PIT_DEPTH));
     }
 }
 
 fn spawn_platforms(platforms: Res<Platforms>, assets: Res<MyAssets>, mut commands: Commands) {
-    for (cell, platform) in platforms.iter() {
-        let material = match platform {
+    for (cell, tile) in platforms.iter() {
+        let material = match tile.platform {
             Platform::Ground => MaterialName::Black,
             Platform::Ice => MaterialName::Cyan,
             Platform::Skate => MaterialName::MidnightBlue,
         };
 
+        // A column from the shared base up to the tile's height
+        let size = platforms.cell_size();
+        let depth = tile.height - TILE_BASE;
         commands.spawn(PbrBundle {
             mesh: assets.mesh(MeshName::Cube),
             material: assets.material(material),
             transform: Transform {
-                translation: platforms.get_point(*cell) - Vec3::Y * 0.5,
+                translation: platforms.get_point(*cell) + Vec3::Y * (tile.height - depth * 0.5),
+                scale: Vec3::new(size, depth, size),
                 ..Default::default()
             },
             ..Default::default()
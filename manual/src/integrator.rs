@@ -0,0 +1,259 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy_extensions::Vec3UpExt;
+
+// How the manual backend advances a body over one tick. Acceleration is constant
+// over the tick and damping only slows the part of the velocity perpendicular to
+// `up`. Run with `--integrator <euler|verlet|exact>` to pick one.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    // Velocity first, then position from the new velocity, with implicit damping
+    SemiImplicitEuler,
+    // Position from the velocity and acceleration at the start of the tick,
+    // velocity from the average acceleration, with trapezoidal damping
+    VelocityVerlet,
+    // Closed-form solution, including exponential decay of the damped velocity
+    #[default]
+    Exact,
+}
+
+impl Integrator {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        match args.iter().position(|arg| arg == "--integrator") {
+            Some(index) => match args.get(index + 1).map(|name| name.parse()) {
+                Some(Ok(integrator)) => integrator,
+                _ => {
+                    error!("--integrator expects euler, verlet or exact");
+                    Self::default()
+                }
+            },
+            None => Self::default(),
+        }
+    }
+
+    // Returns the displacement over the step and the velocity at its end
+    pub fn step(
+        &self,
+        velocity: Vec3,
+        acceleration: Vec3,
+        damping: f32,
+        up: Vec3,
+        dt: f32,
+    ) -> (Vec3, Vec3) {
+        let horizontal_velocity = velocity.horizontal(up);
+        let vertical_velocity = velocity - horizontal_velocity;
+        let horizontal_acceleration = acceleration.horizontal(up);
+        let vertical_acceleration = acceleration - horizontal_acceleration;
+
+        match self {
+            Integrator::SemiImplicitEuler => {
+                let velocity = (horizontal_velocity + horizontal_acceleration * dt)
+                    / (1.0 + damping * dt)
+                    + vertical_velocity
+                    + vertical_acceleration * dt;
+                (velocity * dt, velocity)
+            }
+            Integrator::VelocityVerlet => {
+                let start_acceleration = acceleration - horizontal_velocity * damping;
+                let displacement = velocity * dt + start_acceleration * (0.5 * dt * dt);
+
+                let half_damping = 0.5 * damping * dt;
+                let velocity = (horizontal_velocity * (1.0 - half_damping)
+                    + horizontal_acceleration * dt)
+                    / (1.0 + half_damping)
+                    + vertical_velocity
+                    + vertical_acceleration * dt;
+                (displacement, velocity)
+            }
+            Integrator::Exact => {
+                let (horizontal_displacement, horizontal_velocity) =
+                    damped(horizontal_velocity, horizontal_acceleration, damping, dt);
+                let vertical_displacement =
+                    vertical_velocity * dt + vertical_acceleration * (0.5 * dt * dt);
+                (
+                    horizontal_displacement + vertical_displacement,
+                    horizontal_velocity + vertical_velocity + vertical_acceleration * dt,
+                )
+            }
+        }
+    }
+}
+
+// Solves dv/dt = acceleration - damping * v over `dt`. The velocity decays
+// towards the terminal velocity where the two cancel out.
+fn damped(velocity: Vec3, acceleration: Vec3, damping: f32, dt: f32) -> (Vec3, Vec3) {
+    if damping <= 0.0 {
+        return (
+            velocity * dt + acceleration * (0.5 * dt * dt),
+            velocity + acceleration * dt,
+        );
+    }
+
+    let terminal = acceleration / damping;
+    let decay = (-damping * dt).exp();
+    (
+        terminal * dt + (velocity - terminal) * (1.0 - decay) / damping,
+        terminal + (velocity - terminal) * decay,
+    )
+}
+
+impl FromStr for Integrator {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "euler" => Ok(Integrator::SemiImplicitEuler),
+            "verlet" => Ok(Integrator::VelocityVerlet),
+            "exact" => Ok(Integrator::Exact),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::PHYSICS_DELTA;
+
+    const GRAVITY: f32 = 9.81;
+    const JUMP_HEIGHT: f32 = 3.0;
+
+    // Highest point reached at a tick boundary when jumping with the launch speed
+    // that should reach `JUMP_HEIGHT`
+    fn apex(integrator: Integrator) -> f32 {
+        let dt = PHYSICS_DELTA as f32;
+        let up = Vec3::Y;
+        let mut position = Vec3::ZERO;
+        let mut velocity = up * (2.0 * GRAVITY * JUMP_HEIGHT).sqrt();
+        let mut apex = 0.0_f32;
+
+        while velocity.dot(up) > 0.0 {
+            let (displacement, next) = integrator.step(velocity, -up * GRAVITY, 2.0, up, dt);
+            position += displacement;
+            velocity = next;
+            apex = apex.max(position.dot(up));
+        }
+        apex
+    }
+
+    // The true apex usually falls between two ticks, so sampling at tick
+    // boundaries can miss it by up to a quarter tick of travel
+    fn sampling_tolerance() -> f32 {
+        let dt = PHYSICS_DELTA as f32;
+        GRAVITY * dt * dt / 8.0 + 1e-4
+    }
+
+    #[test]
+    fn exact_reaches_jump_height() {
+        let apex = apex(Integrator::Exact);
+        assert!(
+            (apex - JUMP_HEIGHT).abs() <= sampling_tolerance(),
+            "apex {apex}"
+        );
+    }
+
+    // Constant acceleration is integrated exactly by velocity Verlet
+    #[test]
+    fn verlet_reaches_jump_height() {
+        let apex = apex(Integrator::VelocityVerlet);
+        assert!(
+            (apex - JUMP_HEIGHT).abs() <= sampling_tolerance(),
+            "apex {apex}"
+        );
+    }
+
+    // Semi-implicit Euler moves with the velocity at the end of each tick, which
+    // loses half a tick of travel at the launch speed: it undershoots by v0 * dt / 2
+    #[test]
+    fn euler_undershoots_by_half_a_tick() {
+        let launch_speed = (2.0 * GRAVITY * JUMP_HEIGHT).sqrt();
+        let expected = JUMP_HEIGHT - launch_speed * PHYSICS_DELTA as f32 / 2.0;
+        let apex = apex(Integrator::SemiImplicitEuler);
+        assert!(
+            (apex - expected).abs() <= sampling_tolerance(),
+            "apex {apex}, expected {expected}"
+        );
+    }
+
+    const DAMPING: f32 = 2.0;
+    const SLIDE_SPEED: f32 = 10.0;
+    const SLIDE_TICKS: u32 = 20;
+    // Leading-order error estimates leave out the higher-order terms
+    const ESTIMATE_MARGIN: f32 = 1.05;
+
+    // Displacement and velocity after sliding with nothing but damping
+    fn slide(integrator: Integrator) -> (f32, f32) {
+        let dt = PHYSICS_DELTA as f32;
+        let mut position = Vec3::ZERO;
+        let mut velocity = Vec3::X * SLIDE_SPEED;
+
+        for _ in 0..SLIDE_TICKS {
+            let (displacement, next) = integrator.step(velocity, Vec3::ZERO, DAMPING, Vec3::Y, dt);
+            position += displacement;
+            velocity = next;
+        }
+        (position.x, velocity.x)
+    }
+
+    // v(t) = v0 * e^(-d * t), and the distance covered is what damping took off
+    // the speed divided by d
+    fn analytic_slide() -> (f32, f32) {
+        let time = SLIDE_TICKS as f32 * PHYSICS_DELTA as f32;
+        let velocity = SLIDE_SPEED * (-DAMPING * time).exp();
+        ((SLIDE_SPEED - velocity) / DAMPING, velocity)
+    }
+
+    #[test]
+    fn exact_decays_analytically() {
+        let (position, velocity) = slide(Integrator::Exact);
+        let (expected_position, expected_velocity) = analytic_slide();
+        assert!(
+            (position - expected_position).abs() <= expected_position * 1e-5
+                && (velocity - expected_velocity).abs() <= expected_velocity * 1e-5,
+            "slid {position} at {velocity}, expected {expected_position} at {expected_velocity}"
+        );
+    }
+
+    // Dividing by 1 + h each tick instead of multiplying by e^-h, with h = d * dt,
+    // is first order: the speed is off by about d * t * h / 2 of itself. Euler
+    // still covers exactly what damping took off the speed divided by d.
+    #[test]
+    fn euler_decay_within_first_order_error() {
+        let (position, velocity) = slide(Integrator::SemiImplicitEuler);
+        let (expected_position, expected_velocity) = analytic_slide();
+        let h = DAMPING * PHYSICS_DELTA as f32;
+        let time = SLIDE_TICKS as f32 * PHYSICS_DELTA as f32;
+        let velocity_tolerance = expected_velocity * DAMPING * time * h / 2.0 * ESTIMATE_MARGIN;
+        assert!(
+            (velocity - expected_velocity).abs() <= velocity_tolerance,
+            "speed {velocity}, expected {expected_velocity}"
+        );
+        assert!(
+            (position - expected_position).abs() <= velocity_tolerance / DAMPING,
+            "slid {position}, expected {expected_position}"
+        );
+    }
+
+    // The trapezoidal factor (1 - h/2) / (1 + h/2) is second order: the speed is
+    // off by about d * t * h^2 / 12 of itself. Moving with the damping at the
+    // start of each tick shortens the slide by up to h^2 / 4 of the total.
+    #[test]
+    fn verlet_decay_within_second_order_error() {
+        let (position, velocity) = slide(Integrator::VelocityVerlet);
+        let (expected_position, expected_velocity) = analytic_slide();
+        let h = DAMPING * PHYSICS_DELTA as f32;
+        let time = SLIDE_TICKS as f32 * PHYSICS_DELTA as f32;
+        let velocity_tolerance =
+            expected_velocity * DAMPING * time * h * h / 12.0 * ESTIMATE_MARGIN;
+        assert!(
+            (velocity - expected_velocity).abs() <= velocity_tolerance,
+            "speed {velocity}, expected {expected_velocity}"
+        );
+        assert!(
+            (position - expected_position).abs() <= SLIDE_SPEED / DAMPING * h * h / 4.0,
+            "slid {position}, expected {expected_position}"
+        );
+    }
+}
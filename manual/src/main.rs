@@ -1,19 +1,27 @@
 use bevy::prelude::*;
 
 use bevy_bootstrap::{
-    ActorConfig, CameraFollowExt, MaterialName, MeshName, MyAssets, SpawnActorExt,
+    ActorConfig, CameraFollowExt, LocallyControlled, MaterialName, MeshName, MyAssets,
+    SpawnActorExt,
 };
 
 mod board;
+mod integrator;
 mod physics;
 mod player;
+mod tuning;
 
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            watch_for_changes: true,
+            ..Default::default()
+        }))
         .add_plugin(bevy_bootstrap::AssetsPlugin)
         .add_plugin(bevy_bootstrap::CameraPlugin)
         .add_plugin(bevy_bootstrap::InputPlugin)
+        .add_plugin(bevy_bootstrap::SurfacePlugin)
+        .add_plugin(bevy_bootstrap::LocomotionPlugin)
         .add_plugin(board::BoardPlugin)
         .add_plugin(physics::PhysicsPlugin)
         .add_plugin(player::PlayerPlugin)
@@ -34,21 +42,13 @@ fn setup(mut commands: Commands, assets: Res<MyAssets>) {
         ..Default::default()
     });
 
-    // Floor
-    commands.spawn(PbrBundle {
-        mesh: assets.mesh(MeshName::Cube),
-        material: assets.material(MaterialName::DarkGray),
-        transform: Transform {
-            translation: -Vec3::Y * 0.6,
-            scale: Vec3::new(500.0, 1.0, 500.0),
-            ..Default::default()
-        },
-        ..Default::default()
-    });
-
     // Player
     let player = commands
-        .spawn((TransformBundle::default(), player::PlayerBundle::default()))
+        .spawn((
+            TransformBundle::default(),
+            player::PlayerBundle::default(),
+            LocallyControlled,
+        ))
         .id();
 
     // Actor
@@ -63,4 +63,38 @@ fn setup(mut commands: Commands, assets: Res<MyAssets>) {
 
     // Camera follow
     commands.camera_follow(actor);
+
+    // Crates of increasing mass to push around
+    for (index, mass) in [0.5, 1.0, 4.0].into_iter().enumerate() {
+        let body = commands
+            .spawn((
+                TransformBundle::from(Transform::from_xyz(index as f32 * 3.0 - 3.0, 0.0, 4.0)),
+                physics::PhysicsBundle::dynamic(
+                    physics::Collider {
+                        radius: 0.5,
+                        height: 1.0,
+                    },
+                    mass,
+                ),
+            ))
+            .id();
+
+        commands
+            .spawn((
+                SpatialBundle::default(),
+                physics::PhysicsInterpolation {
+                    target: body,
+                    translate: true,
+                    rotate: false,
+                },
+            ))
+            .with_children(|child| {
+                child.spawn(PbrBundle {
+                    mesh: assets.mesh(MeshName::Cube),
+                    material: assets.material(MaterialName::Silver),
+                    transform: Transform::from_translation(Vec3::Y * 0.5),
+                    ..Default::default()
+                });
+            });
+    }
 }
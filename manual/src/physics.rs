@@ -1,6 +1,10 @@
-use bevy::{ecs::schedule::ShouldRun, prelude::*};
+use bevy::{ecs::schedule::ShouldRun, prelude::*, utils::HashMap};
 
-use bevy_extensions::{MoveTowardsExt, Vec3SwizzlesExt};
+use bevy_bootstrap::GravityField;
+use bevy_extensions::{MoveTowardsExt, Vec3UpExt};
+use bevy_grid::{GridCell, SquareCell};
+
+use crate::{board::Platforms, integrator::Integrator};
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
 struct PhysicsStage;
@@ -17,6 +21,7 @@ pub struct PhysicsPlugin;
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(PhysicsTick::default())
+            .insert_resource(Integrator::from_args())
             .add_stage_after(
                 CoreStage::Update,
                 PhysicsStage,
@@ -43,7 +48,8 @@ impl Plugin for PhysicsPlugin {
                         SystemSet::new()
                             .after(PhysicsLabel::PostUpdate)
                             .with_system(apply_velocity)
-                            .with_system(update_interpolation.after(apply_velocity)),
+                            .with_system(resolve_contacts.after(apply_velocity))
+                            .with_system(update_interpolation.after(resolve_contacts)),
                     ),
             )
             .add_system_set_to_stage(
@@ -110,7 +116,9 @@ impl PhysicsAppExt for App {
 }
 
 const PHYSICS_TICK_RATE: f64 = 20.0;
-const PHYSICS_DELTA: f64 = 1.0 / PHYSICS_TICK_RATE;
+// Tolerance for standing exactly on top of a tile
+const SKIN: f32 = 0.001;
+pub const PHYSICS_DELTA: f64 = 1.0 / PHYSICS_TICK_RATE;
 
 #[derive(Resource, Default)]
 pub struct PhysicsTick {
@@ -163,18 +171,39 @@ pub struct PhysicsBundle {
     force: Force,
     damping: Damping,
     gravity: Gravity,
+    gravity_field: GravityField,
+    collider: Collider,
+    mass: Mass,
+    restitution: Restitution,
+    friction: Friction,
+}
+
+impl PhysicsBundle {
+    // A body that falls under its own gravity instead of having it set every tick
+    pub fn dynamic(collider: Collider, mass: f32) -> Self {
+        Self {
+            damping: Damping(DYNAMIC_DAMPING),
+            gravity: Gravity(DYNAMIC_GRAVITY),
+            collider,
+            mass: Mass(mass),
+            ..Default::default()
+        }
+    }
 }
 
+const DYNAMIC_DAMPING: f32 = 1.0;
+const DYNAMIC_GRAVITY: f32 = 9.81;
+
 #[derive(Component, Default, Deref, DerefMut)]
 pub struct Velocity(pub Vec3);
 
 impl Velocity {
-    pub fn move_towards_xz(&mut self, target: Vec3, acceleration: f32) {
+    pub fn move_towards_horizontal(&mut self, target: Vec3, acceleration: f32, up: Vec3) {
         self.0 = self
             .0
-            .x0z()
+            .horizontal(up)
             .move_towards(target, acceleration)
-            .x_z(self.0.y);
+            .with_vertical(up, self.0.vertical(up));
     }
 }
 
@@ -190,35 +219,102 @@ pub struct Damping(pub f32);
 #[derive(Component, Default)]
 pub struct Gravity(pub f32);
 
+// An upright box standing on the entity's translation
+#[derive(Component)]
+pub struct Collider {
+    pub radius: f32,
+    pub height: f32,
+}
+
+impl Default for Collider {
+    fn default() -> Self {
+        Self {
+            radius: 0.4,
+            height: 2.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Mass(pub f32);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+// Fraction of the approach speed kept after a contact
+#[derive(Component, Default)]
+pub struct Restitution(pub f32);
+
+// Fraction of the contact impulse available to stop sliding
+#[derive(Component)]
+pub struct Friction(pub f32);
+
+impl Default for Friction {
+    fn default() -> Self {
+        Self(0.5)
+    }
+}
+
+impl Collider {
+    pub fn bounds(&self, position: Vec3) -> (Vec3, Vec3) {
+        let extent = Vec3::new(self.radius, 0.0, self.radius);
+        (position - extent, position + extent + Vec3::Y * self.height)
+    }
+}
+
+type VelocityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Velocity,
+        &'static mut Impulse,
+        &'static mut Force,
+        &'static mut Transform,
+        &'static Damping,
+        &'static Gravity,
+        &'static GravityField,
+        &'static Collider,
+    ),
+>;
+
 fn apply_velocity(
-    mut velocity_q: Query<(
-        &mut Velocity,
-        &mut Impulse,
-        &mut Force,
-        &mut Transform,
-        &Damping,
-        &Gravity,
-    )>,
+    mut velocity_q: VelocityQuery,
+    platforms: Res<Platforms>,
+    integrator: Res<Integrator>,
     tick: Res<PhysicsTick>,
 ) {
-    if let Ok((mut velocity, mut impulse, mut force, mut transform, damping, gravity)) =
-        velocity_q.get_single_mut()
+    for (
+        mut velocity,
+        mut impulse,
+        mut force,
+        mut transform,
+        damping,
+        gravity,
+        gravity_field,
+        collider,
+    ) in &mut velocity_q
     {
         let dt = tick.delta();
+        let up = gravity_field.up(transform.translation);
 
-        let mut v = velocity.0;
-        v += impulse.0;
-        v += force.0 * dt;
-        v = (v.x0z() * (1.0 / (1.0 + damping.0 * dt))).x_z(v.y);
-
-        transform.translation += v * dt;
+        let acceleration = force.0 - up * gravity.0;
+        let (motion, mut v) =
+            integrator.step(velocity.0 + impulse.0, acceleration, damping.0, up, dt);
 
-        v.y -= gravity.0 * dt;
+        let blocked = sweep(&platforms, collider, &mut transform.translation, motion);
 
-        if transform.translation.y < 0.0 {
-            transform.translation.y = 0.0;
+        if blocked.x {
+            v.x = 0.0;
+        }
+        if blocked.y {
             v.y = 0.0;
         }
+        if blocked.z {
+            v.z = 0.0;
+        }
 
         velocity.0 = v;
         impulse.0 = Vec3::ZERO;
@@ -226,6 +322,227 @@ fn apply_velocity(
     }
 }
 
+// Buckets for the broad phase; at least as big as the largest body so most
+// pairs share a single cell
+const CONTACT_CELL_SIZE: f32 = 2.0;
+
+#[derive(Default)]
+struct SpatialHash(HashMap<SquareCell, Vec<Entity>>);
+
+impl SpatialHash {
+    // Buckets are kept while in use so their allocations are reused, but dropped
+    // once empty so the map doesn't grow with every cell ever visited
+    fn clear(&mut self) {
+        self.0.retain(|_, bucket| !bucket.is_empty());
+        for bucket in self.0.values_mut() {
+            bucket.clear();
+        }
+    }
+
+    fn insert(&mut self, entity: Entity, min: Vec3, max: Vec3) {
+        let start = SquareCell::from_point(min, CONTACT_CELL_SIZE);
+        let end = SquareCell::from_point(max, CONTACT_CELL_SIZE);
+
+        for column in start.column()..=end.column() {
+            for row in start.row()..=end.row() {
+                self.0
+                    .entry(SquareCell::new(column, row))
+                    .or_default()
+                    .push(entity);
+            }
+        }
+    }
+
+    // Every pair sharing a cell, once. Contacts are resolved one after another, so
+    // the pairs are sorted to resolve them in the same order every run.
+    fn pairs(&self) -> Vec<(Entity, Entity)> {
+        let mut pairs = Vec::new();
+        for bucket in self.0.values() {
+            for (index, a) in bucket.iter().enumerate() {
+                for b in &bucket[index + 1..] {
+                    pairs.push((*a.min(b), *a.max(b)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+}
+
+fn resolve_contacts(
+    mut body_q: Query<(
+        Entity,
+        &mut Transform,
+        &mut Velocity,
+        &Collider,
+        &Mass,
+        &Restitution,
+        &Friction,
+    )>,
+    platforms: Res<Platforms>,
+    mut spatial_hash: Local<SpatialHash>,
+) {
+    spatial_hash.clear();
+    for (entity, transform, _, collider, ..) in &body_q {
+        let (min, max) = collider.bounds(transform.translation);
+        spatial_hash.insert(entity, min, max);
+    }
+
+    for (a, b) in spatial_hash.pairs() {
+        let [a, b] = match body_q.get_many_mut([a, b]) {
+            Ok(bodies) => bodies,
+            Err(_) => continue,
+        };
+        let (_, mut a_transform, mut a_velocity, a_collider, a_mass, a_restitution, a_friction) = a;
+        let (_, mut b_transform, mut b_velocity, b_collider, b_mass, b_restitution, b_friction) = b;
+
+        let (normal, depth) = match penetration(
+            a_collider.bounds(a_transform.translation),
+            b_collider.bounds(b_transform.translation),
+        ) {
+            Some(contact) => contact,
+            None => continue,
+        };
+
+        // Heavier bodies move less, and neither is pushed into a tile
+        let (a_inverse, b_inverse) = (1.0 / a_mass.0, 1.0 / b_mass.0);
+        let inverse_sum = a_inverse + b_inverse;
+        sweep(
+            &platforms,
+            a_collider,
+            &mut a_transform.translation,
+            -normal * depth * a_inverse / inverse_sum,
+        );
+        sweep(
+            &platforms,
+            b_collider,
+            &mut b_transform.translation,
+            normal * depth * b_inverse / inverse_sum,
+        );
+
+        let relative = b_velocity.0 - a_velocity.0;
+        let normal_speed = relative.dot(normal);
+        if normal_speed >= 0.0 {
+            continue;
+        }
+
+        let restitution = (a_restitution.0 + b_restitution.0) * 0.5;
+        let normal_impulse = -(1.0 + restitution) * normal_speed / inverse_sum;
+
+        let tangent = relative - normal * normal_speed;
+        let friction = (a_friction.0 + b_friction.0) * 0.5;
+        let friction_impulse = (tangent.length() / inverse_sum).min(friction * normal_impulse);
+
+        let impulse = normal * normal_impulse - tangent.normalize_or_zero() * friction_impulse;
+        a_velocity.0 -= impulse * a_inverse;
+        b_velocity.0 += impulse * b_inverse;
+    }
+}
+
+// The axis of least overlap between two boxes, pointing from the first to the
+// second, and how far they overlap along it
+fn penetration(a: (Vec3, Vec3), b: (Vec3, Vec3)) -> Option<(Vec3, f32)> {
+    let overlap = a.1.min(b.1) - a.0.max(b.0);
+    if overlap.cmple(Vec3::ZERO).any() {
+        return None;
+    }
+
+    let delta = (b.0 + b.1) - (a.0 + a.1);
+    [
+        (Vec3::X, overlap.x),
+        (Vec3::Y, overlap.y),
+        (Vec3::Z, overlap.z),
+    ]
+    .into_iter()
+    .min_by(|a, b| a.1.total_cmp(&b.1))
+    .map(|(axis, depth)| {
+        if delta.dot(axis) < 0.0 {
+            (-axis, depth)
+        } else {
+            (axis, depth)
+        }
+    })
+}
+
+// Moves the collider one axis at a time, stopping at the first tile face in the
+// way. Returns which axes were blocked.
+fn sweep(platforms: &Platforms, collider: &Collider, position: &mut Vec3, motion: Vec3) -> BVec3 {
+    let x = sweep_horizontal(platforms, collider, position, motion.x, Vec3::X);
+    let z = sweep_horizontal(platforms, collider, position, motion.z, Vec3::Z);
+    let y = sweep_down(platforms, collider, position, motion.y);
+    BVec3::new(x, y, z)
+}
+
+// Steps through each cell boundary the leading face crosses, so fast movement
+// can't pass through a tile
+fn sweep_horizontal(
+    platforms: &Platforms,
+    collider: &Collider,
+    position: &mut Vec3,
+    distance: f32,
+    axis: Vec3,
+) -> bool {
+    if distance == 0.0 {
+        return false;
+    }
+
+    let size = platforms.cell_size();
+    let sign = distance.signum();
+    let lead = position.dot(axis) + collider.radius * sign;
+    let target = lead + distance;
+
+    // A face left resting against a wall may be a rounding error past it, which
+    // mustn't count as having crossed into the wall's cell
+    let start = (lead - SKIN * sign) / size;
+    let mut boundary = if sign > 0.0 {
+        start.ceil() * size
+    } else {
+        start.floor() * size
+    };
+
+    while (boundary - target) * sign < 0.0 {
+        // The next cell along the axis, across the collider's width less the skin
+        // so sliding along a wall it touches doesn't catch on it
+        let (min, max) = collider.bounds(*position);
+        let (near, far) = (boundary, boundary + size * sign);
+        let cross = Vec3::ONE - axis;
+        let min = (min + SKIN) * cross + axis * (near.min(far) + SKIN);
+        let max = (max - SKIN) * cross + axis * (near.max(far) - SKIN);
+
+        if platforms.is_blocked(min, max, position.y + SKIN) {
+            *position += axis * (boundary - lead);
+            return true;
+        }
+
+        boundary += size * sign;
+    }
+
+    *position += axis * distance;
+    false
+}
+
+// Tiles are solid all the way down, so only landing on top of one matters
+fn sweep_down(
+    platforms: &Platforms,
+    collider: &Collider,
+    position: &mut Vec3,
+    distance: f32,
+) -> bool {
+    if distance < 0.0 {
+        let (min, max) = collider.bounds(*position);
+        if let Some(floor) = platforms.floor_height(min, max, position.y + SKIN) {
+            if position.y + distance <= floor {
+                position.y = floor;
+                return true;
+            }
+        }
+    }
+
+    position.y += distance;
+    false
+}
+
 #[derive(Component)]
 pub struct PhysicsInterpolation {
     pub target: Entity,
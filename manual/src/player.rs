@@ -1,37 +1,57 @@
-use bevy::prelude::*;
+use bevy::{ecs::query::ReadOnlyWorldQuery, prelude::*};
 
-use bevy_bootstrap::{Actor, InputAction, InputMovement};
-use bevy_extensions::{FromLookExt, Vec3SwizzlesExt};
+use bevy_bootstrap::{
+    consume_intents, update_locomotion, Actor, Dash, DashStep, Footing, GravityField,
+    GroundTransitions, InputDirection, Locomotion, LocomotionEvents, LocomotionGround,
+    LocomotionTick, LocomotionVelocity, MovementBasis, MovementIntent, MovementMode, ScalarBlend,
+    Scalars, SurfaceMaterial, TuningPlugin,
+};
+use bevy_extensions::{FromLookExt, Vec3UpExt};
 
-use crate::{board::*, physics::*};
+use crate::{board::*, physics::*, tuning::*};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set_to_stage(
-            CoreStage::Update,
-            SystemSet::new().with_system(rotation).with_system(jump),
-        )
-        .add_physics_system_set(
-            PhysicsLabel::PreUpdate,
-            SystemSet::new()
-                .with_system(set_ground_state)
-                .with_system(on_ground_change.after(set_ground_state)),
-        )
-        .add_physics_system_set(PhysicsLabel::Update, SystemSet::new().with_system(movement))
-        .add_physics_system_set(
-            PhysicsLabel::PostUpdate,
-            SystemSet::new().with_system(apply_physics_scalars),
-        );
+        app.add_plugin(TuningPlugin::<ControllerTuning>::new(TUNING_PATH))
+            .insert_resource(GroundTransitions::default())
+            .add_system_to_stage(CoreStage::Update, rotation)
+            .add_physics_system_set(
+                PhysicsLabel::PreUpdate,
+                SystemSet::new()
+                    .with_system(set_ground_state)
+                    .with_system(on_ground_change.after(set_ground_state))
+                    .with_system(blend_scalars.after(on_ground_change))
+                    .with_system(
+                        update_locomotion::<GroundState, Velocity, PhysicsTick>
+                            .after(on_ground_change),
+                    ),
+            )
+            .add_physics_system_set(
+                PhysicsLabel::Update,
+                SystemSet::new()
+                    .with_system(movement)
+                    .with_system(jump)
+                    .with_system(dash)
+                    .with_system(dash_velocity.after(movement).after(dash)),
+            )
+            .add_physics_system(PhysicsLabel::PostUpdate, apply_physics_scalars)
+            .add_physics_system(PhysicsLabel::PostUpdate, consume_intents);
     }
 }
 
 #[derive(Bundle)]
 pub struct PlayerBundle {
     marker: Player,
+    intent: MovementIntent,
+    movement_basis: MovementBasis,
+    input_direction: InputDirection,
     history: PlayerHistory,
     ground_state: GroundState,
+    dash: Dash,
+    locomotion: Locomotion,
+    scalar_blend: ScalarBlend,
 
     #[bundle]
     physics_bundle: PhysicsBundle,
@@ -47,6 +67,9 @@ impl Default for PlayerBundle {
     fn default() -> Self {
         Self {
             marker: Player,
+            intent: MovementIntent::default(),
+            movement_basis: MovementBasis::Camera,
+            input_direction: InputDirection::default(),
             history: PlayerHistory {
                 input_on_ground_change: Vec3::ZERO,
                 velocity_on_ground_change: Vec3::ZERO,
@@ -54,6 +77,9 @@ impl Default for PlayerBundle {
                 previous_ground_state: GroundState::default(),
             },
             ground_state: GroundState::default(),
+            dash: Dash::default(),
+            locomotion: Locomotion::default(),
+            scalar_blend: ScalarBlend::new(SurfaceMaterial::default().scalars()),
             physics_bundle: PhysicsBundle::default(),
             speed_scale: SpeedScale(1.0),
             acceleration_scale: AccelerationScale(1.0),
@@ -90,122 +116,154 @@ struct PlayerHistory {
     previous_ground_state: GroundState,
 }
 
-#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 enum GroundState {
     None,
-    #[default]
-    Normal,
-    Slippery,
-    Forward,
+    Ground(SurfaceMaterial),
 }
 
-struct Scalars {
-    speed: f32,
-    acceleration: f32,
-    damping: f32,
-    gravity: f32,
-    jump_height: f32,
+impl Default for GroundState {
+    fn default() -> Self {
+        Self::Ground(SurfaceMaterial::default())
+    }
 }
 
-const BASE_SPEED: f32 = 15.0;
-const BASE_ACCELERATION: f32 = BASE_SPEED * 4.0;
-const BASE_DAMPING: f32 = BASE_SPEED * 0.4;
-const BASE_GRAVITY: f32 = 9.81;
-const BASE_JUMP_HEIGHT: f32 = 2.0;
+impl LocomotionGround for GroundState {
+    fn footing(&self) -> Footing {
+        match self {
+            GroundState::None => Footing::Air,
+            GroundState::Ground(_) => Footing::Ground,
+        }
+    }
+}
+
+impl LocomotionVelocity for Velocity {
+    fn linear(&self) -> Vec3 {
+        self.0
+    }
+}
+
+impl LocomotionTick for PhysicsTick {
+    fn delta(&self) -> f32 {
+        PhysicsTick::delta(self)
+    }
+}
 
 impl GroundState {
-    fn scalars(&self) -> Scalars {
+    fn scalars(&self, tuning: &ControllerTuning) -> Scalars {
         match self {
-            GroundState::None => Scalars {
-                speed: 1.0,
-                acceleration: 0.2,
-                damping: 0.2,
-                gravity: 1.0,
-                jump_height: 0.0,
-            },
-            GroundState::Normal => Scalars {
-                speed: 1.0,
-                acceleration: 1.0,
-                damping: 1.0,
-                gravity: 1.0,
-                jump_height: 1.0,
-            },
-            GroundState::Slippery => Scalars {
-                speed: 1.5,
-                acceleration: 0.1,
-                damping: 0.01,
-                gravity: 1.0,
-                jump_height: 0.0,
-            },
-            GroundState::Forward => Scalars {
-                speed: 1.0,
-                acceleration: 1.0,
-                damping: 0.0,
-                gravity: 1.0,
-                jump_height: 0.0,
-            },
+            GroundState::None => tuning.airborne,
+            GroundState::Ground(surface) => surface.scalars(),
         }
     }
 }
 
+// Standing within this distance of a tile top counts as grounded
+const GROUND_TOLERANCE: f32 = 0.01;
+
 fn set_ground_state(
-    mut player_q: Query<(&mut GroundState, &mut PlayerHistory, &Transform), With<Player>>,
+    mut player_q: Query<
+        (&mut GroundState, &mut PlayerHistory, &Transform, &Collider),
+        With<Player>,
+    >,
     platforms: Res<Platforms>,
+    tuning: Res<ControllerTuning>,
 ) {
-    let (mut ground_state, mut player_history, transform) = player_q.single_mut();
-    let pos = transform.translation;
-
-    let new_ground_state = if pos.y > 0.0 {
-        GroundState::None
-    } else if let Some(platform) = platforms.get_tile_from_point(pos) {
-        match platform {
-            Platform::Ground => GroundState::Normal,
-            Platform::Ice => GroundState::Slippery,
-            Platform::Skate => GroundState::Forward,
+    for (mut ground_state, mut player_history, transform, collider) in &mut player_q {
+        let pos = transform.translation;
+        let (min, max) = collider.bounds(pos);
+        let grounded = platforms
+            .floor_height(min, max, pos.y + GROUND_TOLERANCE)
+            .is_some_and(|floor| pos.y - floor <= GROUND_TOLERANCE);
+
+        let new_ground_state = if !grounded {
+            GroundState::None
+        } else if let Some(tile) = platforms.get_tile_from_point(pos) {
+            GroundState::Ground(tile.platform.surface(&tuning.surfaces))
+        } else {
+            GroundState::default()
+        };
+
+        if *ground_state != new_ground_state {
+            player_history.previous_ground_state = *ground_state;
+            *ground_state = new_ground_state;
         }
-    } else {
-        GroundState::Normal
-    };
-
-    if *ground_state != new_ground_state {
-        player_history.previous_ground_state = *ground_state;
-        *ground_state = new_ground_state;
     }
 }
 
+type GroundChangeQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static GroundState,
+        &'static Velocity,
+        &'static InputDirection,
+        ChangeTrackers<GroundState>,
+        &'static mut PlayerHistory,
+        &'static mut ScalarBlend,
+    ),
+    With<Player>,
+>;
+
 fn on_ground_change(
-    mut player_q: Query<
-        (
-            &GroundState,
-            &Velocity,
-            &mut PlayerHistory,
-            &mut SpeedScale,
-            &mut AccelerationScale,
-            &mut DampingScale,
-            &mut GravityScale,
-            &mut JumpHeightScale,
-        ),
-        (Changed<GroundState>, With<Player>),
-    >,
-    actor_q: Query<&Transform, With<Actor>>,
-    input: Res<InputMovement>,
+    mut player_q: GroundChangeQuery,
+    actor_q: Query<(&Transform, &PhysicsInterpolation), With<Actor>>,
+    transitions: Res<GroundTransitions>,
+    tuning: Res<ControllerTuning>,
 ) {
-    if let Ok((
+    for (
+        player,
         ground_state,
         velocity,
+        input_direction,
+        ground_state_tracker,
         mut player_history,
+        mut scalar_blend,
+    ) in &mut player_q
+    {
+        if ground_state_tracker.is_changed() {
+            player_history.input_on_ground_change = input_direction.get();
+            player_history.velocity_on_ground_change = velocity.0;
+            player_history.forward_on_ground_change = actor_forward(&actor_q, player);
+        } else if !tuning.is_changed() {
+            continue;
+        }
+
+        // Reloaded tuning restarts the blend towards the current state's new scalars
+        scalar_blend.start(
+            ground_state.scalars(&tuning),
+            *ground_state == GroundState::None,
+            &transitions,
+        );
+    }
+}
+
+type BlendQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut ScalarBlend,
+        &'static mut SpeedScale,
+        &'static mut AccelerationScale,
+        &'static mut DampingScale,
+        &'static mut GravityScale,
+        &'static mut JumpHeightScale,
+    ),
+    With<Player>,
+>;
+
+fn blend_scalars(mut player_q: BlendQuery, tick: Res<PhysicsTick>) {
+    for (
+        mut scalar_blend,
         mut speed_scale,
         mut acceleration_scale,
         mut damping_scale,
         mut gravity_scale,
         mut jump_height_scale,
-    )) = player_q.get_single_mut()
+    ) in &mut player_q
     {
-        player_history.input_on_ground_change = input.x0z();
-        player_history.velocity_on_ground_change = velocity.0;
-        player_history.forward_on_ground_change = actor_q.single().forward();
-
-        let scalars = ground_state.scalars();
+        let scalars = scalar_blend.step(tick.delta());
         speed_scale.0 = scalars.speed;
         acceleration_scale.0 = scalars.acceleration;
         damping_scale.0 = scalars.damping;
@@ -214,82 +272,271 @@ fn on_ground_change(
     }
 }
 
+type ActorQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Transform, &'static PhysicsInterpolation),
+    (With<Actor>, Without<Player>),
+>;
+
+type MovementQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Velocity,
+        &'static GroundState,
+        &'static PlayerHistory,
+        &'static SpeedScale,
+        &'static AccelerationScale,
+        &'static Dash,
+        &'static GravityField,
+        &'static Transform,
+        &'static InputDirection,
+    ),
+    With<Player>,
+>;
+
 fn movement(
-    mut player_q: Query<
-        (
-            &mut Velocity,
-            &GroundState,
-            &PlayerHistory,
-            &SpeedScale,
-            &AccelerationScale,
-        ),
-        With<Player>,
-    >,
-    actor_q: Query<&Transform, (With<Actor>, Without<Player>)>,
-    input: Res<InputMovement>,
+    mut player_q: MovementQuery,
+    actor_q: ActorQuery,
+    tuning: Res<ControllerTuning>,
     tick: Res<PhysicsTick>,
 ) {
-    let (mut velocity, ground_state, player_history, speed_scale, acceleration_scale) =
-        player_q.single_mut();
-
     let dt = tick.delta();
-    let speed = BASE_SPEED * speed_scale.0;
-    let acceleration = BASE_ACCELERATION * acceleration_scale.0 * dt;
-
-    match ground_state {
-        GroundState::Forward => {
-            let forward_speed = player_history
-                .velocity_on_ground_change
-                .x0z()
-                .length()
-                .max(1.0);
-            velocity.move_towards_xz(actor_q.single().forward() * forward_speed, acceleration);
+
+    for (
+        player,
+        mut velocity,
+        ground_state,
+        player_history,
+        speed_scale,
+        acceleration_scale,
+        dash,
+        gravity_field,
+        transform,
+        input_direction,
+    ) in &mut player_q
+    {
+        if dash.is_active() {
+            continue;
         }
-        _ => {
-            if !input.is_zero() {
-                velocity.move_towards_xz(input.x0z() * speed, acceleration);
+
+        let up = gravity_field.up(transform.translation);
+        let speed = tuning.speed * speed_scale.0;
+        let acceleration = tuning.acceleration * acceleration_scale.0 * dt;
+
+        match ground_state {
+            GroundState::Ground(SurfaceMaterial {
+                mode: MovementMode::Forward { .. },
+                ..
+            }) => {
+                let forward_speed = player_history
+                    .velocity_on_ground_change
+                    .horizontal(up)
+                    .length()
+                    .max(1.0);
+                velocity.move_towards_horizontal(
+                    actor_forward(&actor_q, player) * forward_speed,
+                    acceleration,
+                    up,
+                );
+            }
+            _ => {
+                if !input_direction.is_zero() {
+                    let direction = input_direction.get().horizontal(up).normalize_or_zero();
+                    velocity.move_towards_horizontal(direction * speed, acceleration, up);
+                }
             }
         }
     }
 }
 
+type RotationQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GravityField,
+        &'static Transform,
+        &'static InputDirection,
+    ),
+    (With<Player>, Without<Actor>),
+>;
+
 fn rotation(
-    mut actor_q: Query<&mut Transform, With<Actor>>,
-    input: Res<InputMovement>,
+    mut actor_q: Query<(&mut Transform, &PhysicsInterpolation), With<Actor>>,
+    player_q: RotationQuery,
+    tuning: Res<ControllerTuning>,
     time: Res<Time>,
 ) {
-    if input.is_zero() {
-        return;
-    }
+    for (mut transform, interpolation) in &mut actor_q {
+        if let Ok((gravity_field, player_transform, input_direction)) =
+            player_q.get(interpolation.target)
+        {
+            let up = gravity_field.up(player_transform.translation);
+            let direction = input_direction.get().horizontal(up);
+
+            if direction == Vec3::ZERO {
+                continue;
+            }
 
-    const ROTATION_SPEED: f32 = 15.0;
+            transform.rotation = Quat::slerp(
+                transform.rotation,
+                Quat::from_look(direction, up),
+                tuning.rotation_speed * time.delta_seconds(),
+            );
+        }
+    }
+}
 
-    let mut transform = actor_q.single_mut();
-    transform.rotation = Quat::slerp(
-        transform.rotation,
-        Quat::from_look(input.x0z(), Vec3::Y),
-        ROTATION_SPEED * time.delta_seconds(),
-    );
+fn actor_forward(
+    actor_q: &Query<(&Transform, &PhysicsInterpolation), impl ReadOnlyWorldQuery>,
+    player: Entity,
+) -> Vec3 {
+    actor_q
+        .iter()
+        .find(|(_, interpolation)| interpolation.target == player)
+        .map_or(Vec3::NEG_Z, |(transform, _)| transform.forward())
 }
 
+type JumpQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Impulse,
+        &'static mut Locomotion,
+        &'static GravityScale,
+        &'static JumpHeightScale,
+        &'static GravityField,
+        &'static Transform,
+        &'static MovementIntent,
+    ),
+    With<Player>,
+>;
+
 fn jump(
-    mut player_q: Query<(&mut Impulse, &GravityScale, &JumpHeightScale), With<Player>>,
-    input_action: Res<InputAction>,
+    mut player_q: JumpQuery,
+    mut events: LocomotionEvents<PhysicsTick>,
+    tuning: Res<ControllerTuning>,
 ) {
-    if let InputAction::Jump = *input_action {
-        let (mut impulse, gravity_scale, jump_height_scale) = player_q.single_mut();
+    for (
+        player,
+        mut impulse,
+        mut locomotion,
+        gravity_scale,
+        jump_height_scale,
+        gravity_field,
+        transform,
+        intent,
+    ) in &mut player_q
+    {
+        if !intent.jump.just_pressed() {
+            continue;
+        }
 
-        impulse.y += f32::sqrt(
-            2.0 * BASE_GRAVITY * gravity_scale.0 * BASE_JUMP_HEIGHT * jump_height_scale.0,
+        // States that can't jump scale the height down to nothing
+        let jump_speed = f32::sqrt(
+            2.0 * tuning.gravity * gravity_scale.0 * tuning.jump_height * jump_height_scale.0,
         );
+        if jump_speed > 0.0 {
+            locomotion.jump(player, &mut events);
+            impulse.0 += gravity_field.up(transform.translation) * jump_speed;
+        }
     }
 }
 
-fn apply_physics_scalars(
-    mut player_q: Query<(&mut Damping, &mut Gravity, &DampingScale, &GravityScale), With<Player>>,
+type DashQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Dash,
+        &'static GravityField,
+        &'static Transform,
+        &'static MovementIntent,
+        &'static InputDirection,
+    ),
+    With<Player>,
+>;
+
+fn dash(mut player_q: DashQuery, actor_q: ActorQuery) {
+    for (player, mut dash, gravity_field, transform, intent, input_direction) in &mut player_q {
+        if !intent.dash.just_pressed() {
+            continue;
+        }
+
+        let up = gravity_field.up(transform.translation);
+        let direction = if input_direction.is_zero() {
+            actor_forward(&actor_q, player).horizontal(up)
+        } else {
+            input_direction.get().horizontal(up)
+        };
+
+        dash.start(direction.normalize_or_zero());
+    }
+}
+
+type DashVelocityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Dash,
+        &'static mut Velocity,
+        &'static GroundState,
+        &'static SpeedScale,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
+
+fn dash_velocity(
+    mut player_q: DashVelocityQuery,
+    tuning: Res<ControllerTuning>,
+    tick: Res<PhysicsTick>,
 ) {
-    let (mut damping, mut gravity, damping_scale, gravity_scale) = player_q.single_mut();
+    for (mut dash, mut velocity, ground_state, speed_scale, gravity_field, transform) in
+        &mut player_q
+    {
+        match dash.step(tick.delta()) {
+            DashStep::Idle => {
+                if *ground_state != GroundState::None {
+                    dash.refill();
+                }
+            }
+            DashStep::Active(dash_velocity) => {
+                velocity.0 = dash_velocity;
+            }
+            DashStep::Finished(direction) => {
+                let up = gravity_field.up(transform.translation);
+                velocity.0 = (direction * tuning.speed * speed_scale.0)
+                    .with_vertical(up, velocity.0.vertical(up));
+            }
+        }
+    }
+}
 
-    damping.0 = BASE_DAMPING * damping_scale.0;
-    gravity.0 = BASE_GRAVITY * gravity_scale.0;
+fn apply_physics_scalars(
+    mut player_q: Query<
+        (
+            &mut Damping,
+            &mut Gravity,
+            &DampingScale,
+            &GravityScale,
+            &Dash,
+        ),
+        With<Player>,
+    >,
+    tuning: Res<ControllerTuning>,
+) {
+    for (mut damping, mut gravity, damping_scale, gravity_scale, dash) in &mut player_q {
+        if dash.is_active() {
+            damping.0 = 0.0;
+            gravity.0 = 0.0;
+        } else {
+            damping.0 = tuning.damping * damping_scale.0;
+            gravity.0 = tuning.gravity * gravity_scale.0;
+        }
+    }
 }
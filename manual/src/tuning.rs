@@ -0,0 +1,108 @@
+use bevy::{prelude::*, reflect::TypeUuid};
+use bevy_bootstrap::{non_negative, MovementMode, Scalars, SurfaceMaterial, Surfaces, Tuning};
+use serde::Deserialize;
+
+use crate::board::Platform;
+
+pub const TUNING_PATH: &str = "manual.tuning.ron";
+
+#[derive(Resource, TypeUuid, Deserialize, Debug, Clone, PartialEq)]
+#[uuid = "e9314629-5e65-4b8c-8d51-c273d310d3a2"]
+pub struct ControllerTuning {
+    pub speed: f32,
+    pub acceleration: f32,
+    pub damping: f32,
+    pub gravity: f32,
+    pub jump_height: f32,
+    pub rotation_speed: f32,
+    pub airborne: Scalars,
+    pub surfaces: Surfaces,
+}
+
+// Used until the asset has loaded, and whenever it fails to
+impl Default for ControllerTuning {
+    fn default() -> Self {
+        Self {
+            speed: 15.0,
+            acceleration: 60.0,
+            damping: 6.0,
+            gravity: 9.81,
+            jump_height: 2.0,
+            rotation_speed: 15.0,
+            airborne: Scalars {
+                speed: 1.0,
+                acceleration: 0.2,
+                damping: 0.2,
+                gravity: 1.0,
+                jump_height: 0.0,
+            },
+            surfaces: Surfaces::from([
+                (
+                    "ice",
+                    SurfaceMaterial {
+                        speed: 1.5,
+                        acceleration: 0.1,
+                        damping: 0.01,
+                        jump_height: 0.0,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "skate",
+                    SurfaceMaterial {
+                        damping: 0.0,
+                        jump_height: 0.0,
+                        mode: MovementMode::Forward {
+                            turn_rate: f32::INFINITY,
+                        },
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        }
+    }
+}
+
+impl Tuning for ControllerTuning {
+    const EXTENSION: &'static str = "tuning.ron";
+
+    fn validate(&self) -> Result<(), String> {
+        non_negative("speed", self.speed)?;
+        non_negative("acceleration", self.acceleration)?;
+        non_negative("damping", self.damping)?;
+        non_negative("gravity", self.gravity)?;
+        non_negative("jump_height", self.jump_height)?;
+        non_negative("rotation_speed", self.rotation_speed)?;
+        self.airborne
+            .validate()
+            .map_err(|err| format!("airborne.{err}"))?;
+        self.surfaces
+            .validate()
+            .map_err(|err| format!("surfaces.{err}"))?;
+
+        // Every platform the board places needs its material
+        for platform in Platform::ALL {
+            if let Some(name) = platform.surface_name() {
+                if !self.surfaces.contains_key(name) {
+                    return Err(format!("surfaces is missing {name}"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The fallback and the shipped asset are kept in step
+    #[test]
+    fn asset_matches_default() {
+        let tuning: ControllerTuning =
+            ron::de::from_str(include_str!("../../assets/manual.tuning.ron")).unwrap();
+        assert_eq!(tuning.validate(), Ok(()));
+        assert_eq!(tuning, ControllerTuning::default());
+    }
+}
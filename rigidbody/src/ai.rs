@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::MovementIntent;
+use bevy_extensions::*;
+use bevy_physics::*;
+
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(PhysicsLabel::Input, wander)
+            .add_snapshot_component::<Wander>();
+    }
+}
+
+// Each wanderer draws from its own seeded generator, so a rollback replays the
+// same targets and waits
+#[derive(Component, Clone)]
+pub struct Wander {
+    target: Vec3,
+    wait: f32,
+    seed: u64,
+}
+
+impl Wander {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            target: Vec3::ZERO,
+            wait: 0.0,
+            seed,
+        }
+    }
+}
+
+const WANDER_RANGE: f32 = 8.0;
+const ARRIVE_DISTANCE: f32 = 0.5;
+const MIN_WAIT: f32 = 1.0;
+const MAX_WAIT: f32 = 3.0;
+
+pub fn wander(
+    mut wander_q: Query<(&mut Wander, &mut MovementIntent, &Transform)>,
+    tick: Res<PhysicsTick>,
+) {
+    for (mut wander, mut intent, transform) in &mut wander_q {
+        let offset = (wander.target - transform.translation).xz();
+
+        if offset.length() > ARRIVE_DISTANCE {
+            intent.movement = offset.normalize();
+            continue;
+        }
+
+        intent.movement = Vec2::ZERO;
+        wander.wait -= tick.delta();
+
+        if wander.wait <= 0.0 {
+            let rng = fastrand::Rng::with_seed(wander.seed);
+            let target = (Vec2::new(rng.f32(), rng.f32()) * 2.0 - 1.0) * WANDER_RANGE;
+            wander.target = Vec3::new(target.x, 0.0, target.y);
+            wander.wait = MIN_WAIT + rng.f32() * (MAX_WAIT - MIN_WAIT);
+            wander.seed = rng.u64(..);
+        }
+    }
+}
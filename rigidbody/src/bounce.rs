@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::{BouncePad, Dash, GravityField, Locomotion, MovementIntent};
+use bevy_physics::*;
+
+use crate::{ControllerTuning, GroundState, Player};
+
+pub struct BouncePlugin;
+
+impl Plugin for BouncePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(
+            PhysicsLabel::PreUpdate,
+            bounce
+                .after(crate::set_ground_state)
+                .before(crate::on_ground_change),
+        );
+    }
+}
+
+const JUMP_BOOST: f32 = 1.5;
+
+type BounceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut GroundState,
+        &'static mut Velocity,
+        &'static mut Dash,
+        &'static mut Locomotion,
+        &'static mut MovementIntent,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
+
+fn bounce(
+    mut player_q: BounceQuery,
+    bounce_pad_q: Query<&BouncePad>,
+    physics: Res<PhysicsContext>,
+    tuning: Res<ControllerTuning>,
+) {
+    for (
+        player,
+        mut ground_state,
+        mut velocity,
+        mut dash,
+        mut locomotion,
+        mut intent,
+        gravity_field,
+        transform,
+    ) in &mut player_q
+    {
+        let bounce_pad_hit = physics
+            .contacts_with(player)
+            .filter(|contact| contact.has_any_active_contacts())
+            .find_map(|contact| {
+                let other = if contact.collider1() == player {
+                    contact.collider2()
+                } else {
+                    contact.collider1()
+                };
+                bounce_pad_q.get(other).ok()
+            });
+
+        if let Some(bounce_pad) = bounce_pad_hit {
+            let boost = if intent.jump.held() { JUMP_BOOST } else { 1.0 };
+            let launch = bounce_pad.launch_velocity(
+                tuning.gravity * tuning.airborne.gravity,
+                gravity_field.up(transform.translation),
+                boost,
+            );
+
+            // Contacts linger for a tick after launch, so don't launch twice.
+            if velocity.linvel.dot(launch.normalize()) < launch.length() * 0.5 {
+                velocity.linvel = launch;
+                *ground_state = GroundState::None;
+                dash.refill();
+                locomotion.reset_jump();
+                // A press on impact went into the boost, so it mustn't jump as well
+                intent.jump.consume();
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::{Actor, MovementIntent};
+use bevy_physics::*;
+
+use crate::Player;
+
+pub struct CrouchPlugin;
+
+impl Plugin for CrouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::Update, squash)
+            .add_physics_system(PhysicsLabel::PreUpdate, stance)
+            .add_snapshot_component::<Stance>();
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Stance {
+    #[default]
+    Standing,
+    Crouching,
+}
+
+pub const CAPSULE_RADIUS: f32 = 0.5;
+const STANDING_HEIGHT: f32 = 2.0;
+const CROUCHING_HEIGHT: f32 = 1.2;
+
+impl Stance {
+    pub fn height(&self) -> f32 {
+        match self {
+            Stance::Standing => STANDING_HEIGHT,
+            Stance::Crouching => CROUCHING_HEIGHT,
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        match self {
+            Stance::Standing => 1.0,
+            Stance::Crouching => 0.5,
+        }
+    }
+
+    pub fn collider(&self) -> Collider {
+        Collider::capsule(
+            Vec3::Y * CAPSULE_RADIUS,
+            Vec3::Y * (self.height() - CAPSULE_RADIUS),
+            CAPSULE_RADIUS,
+        )
+    }
+}
+
+fn stance(
+    mut player_q: Query<
+        (
+            Entity,
+            &mut Stance,
+            &mut Collider,
+            &Transform,
+            &MovementIntent,
+        ),
+        With<Player>,
+    >,
+    physics: Res<PhysicsContext>,
+) {
+    for (player, mut stance, mut collider, transform, intent) in &mut player_q {
+        let new_stance = if intent.crouch.held()
+            || (*stance == Stance::Crouching && is_ceiling_blocked(player, transform, &physics))
+        {
+            Stance::Crouching
+        } else {
+            Stance::Standing
+        };
+
+        if *stance != new_stance {
+            *stance = new_stance;
+            *collider = new_stance.collider();
+        }
+    }
+}
+
+fn is_ceiling_blocked(player: Entity, transform: &Transform, physics: &PhysicsContext) -> bool {
+    // Only the space gained by standing up is checked, so the ground is never hit.
+    let radius = CAPSULE_RADIUS * 0.9;
+    let head = Collider::capsule(
+        Vec3::Y * CROUCHING_HEIGHT,
+        Vec3::Y * (STANDING_HEIGHT - radius),
+        radius,
+    );
+
+    let mut blocked = false;
+    physics.intersections_with_shape(
+        transform.translation,
+        transform.rotation,
+        &head,
+        QueryFilter {
+            flags: QueryFilterFlags::EXCLUDE_SENSORS,
+            exclude_rigid_body: Some(player),
+            ..Default::default()
+        },
+        |_| {
+            blocked = true;
+            false
+        },
+    );
+
+    blocked
+}
+
+fn squash(
+    mut actor_q: Query<(&mut Transform, &PhysicsInterpolation), With<Actor>>,
+    player_q: Query<&Stance, With<Player>>,
+    time: Res<Time>,
+) {
+    const SQUASH_SPEED: f32 = 15.0;
+
+    for (mut transform, interpolation) in &mut actor_q {
+        if let Ok(stance) = player_q.get(interpolation.target) {
+            let target = Vec3::new(1.0, stance.height() / STANDING_HEIGHT, 1.0);
+            transform.scale = transform
+                .scale
+                .lerp(target, (SQUASH_SPEED * time.delta_seconds()).min(1.0));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::{Actor, Dash, DashStep, GravityField, InputDirection, MovementIntent};
+use bevy_extensions::*;
+use bevy_physics::*;
+
+use crate::{ControllerTuning, GroundState, Player, SpeedScale};
+
+pub struct DashPlugin;
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system_set(
+            PhysicsLabel::Update,
+            SystemSet::new()
+                .with_system(dash)
+                .with_system(dash_velocity.after(dash).after(crate::movement)),
+        )
+        .add_snapshot_component::<Dash>();
+    }
+}
+
+type ActorQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Transform, &'static PhysicsInterpolation),
+    (With<Actor>, Without<Player>),
+>;
+
+fn dash(
+    mut player_q: Query<
+        (
+            &mut Dash,
+            &InputDirection,
+            &GravityField,
+            &Transform,
+            &MovementIntent,
+        ),
+        With<Player>,
+    >,
+    actor_q: ActorQuery,
+) {
+    for (actor_transform, interpolation) in &actor_q {
+        if let Ok((mut dash, input_direction, gravity_field, transform, intent)) =
+            player_q.get_mut(interpolation.target)
+        {
+            if !intent.dash.just_pressed() {
+                continue;
+            }
+
+            let direction = if input_direction.is_zero() {
+                actor_transform.forward()
+            } else {
+                input_direction.get()
+            };
+
+            dash.start(
+                direction
+                    .horizontal(gravity_field.up(transform.translation))
+                    .normalize_or_zero(),
+            );
+        }
+    }
+}
+
+type DashVelocityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Dash,
+        &'static mut Velocity,
+        &'static mut GravityScale,
+        &'static GroundState,
+        &'static SpeedScale,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
+
+fn dash_velocity(
+    mut player_q: DashVelocityQuery,
+    tuning: Res<ControllerTuning>,
+    tick: Res<PhysicsTick>,
+) {
+    for (
+        mut dash,
+        mut velocity,
+        mut gravity_scale,
+        ground_state,
+        speed_scale,
+        gravity_field,
+        transform,
+    ) in &mut player_q
+    {
+        match dash.step(tick.delta()) {
+            DashStep::Idle => {
+                // Hanging, climbing and swimming don't count as touching down
+                if let GroundState::Ground(_) = ground_state {
+                    dash.refill();
+                }
+            }
+            DashStep::Active(dash_velocity) => {
+                velocity.linvel = dash_velocity;
+                gravity_scale.0 = 0.0;
+            }
+            DashStep::Finished(direction) => {
+                let up = gravity_field.up(transform.translation);
+                velocity.linvel = (direction * tuning.speed * speed_scale.0)
+                    .with_vertical(up, velocity.linvel.vertical(up));
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use bevy_bootstrap::{DebugDraw, GravityField};
+use bevy_physics::*;
+
+use crate::{GroundState, Player, GROUND_RAY_LENGTH, GROUND_RAY_OFFSET};
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::Update, draw_controllers)
+            .add_physics_system(PhysicsLabel::PostUpdate, log_transitions);
+    }
+}
+
+const NORMAL_LENGTH: f32 = 1.0;
+// Velocity lines are drawn at this many metres per m/s
+const VELOCITY_SCALE: f32 = 0.2;
+
+// Ground states are tracked even while disabled so enabling doesn't log every
+// character at once
+fn log_transitions(
+    player_q: Query<(Entity, &GroundState), With<Player>>,
+    mut previous: Local<HashMap<Entity, GroundState>>,
+    debug_draw: Res<DebugDraw>,
+    tick: Res<PhysicsTick>,
+) {
+    // Rollback replays ticks that were already logged
+    if tick.is_resimulating() {
+        return;
+    }
+
+    for (entity, ground_state) in &player_q {
+        let from = match previous.insert(entity, *ground_state) {
+            Some(from) if from != *ground_state => from,
+            _ => continue,
+        };
+
+        if debug_draw.is_enabled() {
+            info!(
+                "tick {}: {:?} {:?} -> {:?}",
+                tick.count(),
+                entity,
+                from,
+                ground_state
+            );
+        }
+    }
+}
+
+fn draw_controllers(
+    player_q: Query<(Entity, &GroundState, &Velocity, &GravityField, &Transform), With<Player>>,
+    physics: Res<PhysicsContext>,
+    mut debug_draw: ResMut<DebugDraw>,
+) {
+    if !debug_draw.is_enabled() {
+        return;
+    }
+
+    for (player, ground_state, velocity, gravity_field, transform) in &player_q {
+        let up = gravity_field.up(transform.translation);
+        let origin = transform.translation + up * GROUND_RAY_OFFSET;
+
+        // The ground ray is colored by the current ground state
+        let state_color = match ground_state {
+            GroundState::None => Color::YELLOW,
+            GroundState::Ground(_) => Color::GREEN,
+            GroundState::Hanging | GroundState::Climbing => Color::ORANGE,
+            GroundState::Swimming => Color::BLUE,
+        };
+        debug_draw.ray(origin, -up * GROUND_RAY_LENGTH, state_color);
+
+        let hit = physics.cast_ray_and_get_normal(
+            origin,
+            -up,
+            GROUND_RAY_LENGTH,
+            true,
+            QueryFilter {
+                flags: QueryFilterFlags::EXCLUDE_SENSORS,
+                exclude_rigid_body: Some(player),
+                ..Default::default()
+            },
+        );
+        if let Some((_, intersection)) = hit {
+            debug_draw.ray(
+                intersection.point,
+                intersection.normal * NORMAL_LENGTH,
+                Color::CYAN,
+            );
+        }
+
+        debug_draw.ray(
+            transform.translation,
+            velocity.linvel * VELOCITY_SCALE,
+            Color::RED,
+        );
+    }
+}
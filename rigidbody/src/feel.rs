@@ -0,0 +1,362 @@
+use std::{
+    fmt,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use bevy::{ecs::system::CommandQueue, prelude::*, time::TimeUpdateStrategy};
+
+use bevy_bootstrap::*;
+use bevy_extensions::*;
+use bevy_physics::*;
+
+use crate::{simulation, spawn_character, ControllerTuning};
+
+// Far enough from the level that nothing else is in reach
+const ARENA: Vec3 = Vec3::new(1000.0, 0.0, 0.0);
+const ARENA_SIZE: f32 = 400.0;
+const START: Vec3 = Vec3::new(-150.0, 0.0, 0.0);
+
+const FRAME: Duration = Duration::from_millis(10);
+const SETTLE_TICKS: u32 = 20;
+const RUN_TICKS: u32 = 80;
+const MAX_TICKS: u32 = 400;
+const MAX_SPEED_FRACTION: f32 = 0.95;
+const STOPPED_SPEED: f32 = 0.1;
+
+// A headless app with a single character on a flat block, stepped one physics
+// tick at a time with scripted input
+pub struct FeelHarness {
+    app: App,
+    player: Entity,
+    time: Instant,
+}
+
+impl FeelHarness {
+    pub fn new(block: Block) -> Self {
+        let time = Instant::now();
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(HeadlessPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualInstant(time));
+        simulation(&mut app);
+
+        // Runs startup so the assets exist, then waits for the tuning so it doesn't
+        // change mid-measurement
+        loop {
+            app.update();
+            let handle = app.world.resource::<TuningHandle<ControllerTuning>>();
+            if handle.is_settled(app.world.resource::<AssetServer>()) {
+                break;
+            }
+        }
+        app.update();
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &app.world);
+        commands.spawn_block(
+            app.world.resource::<MyAssets>(),
+            block,
+            Transform {
+                translation: ARENA - Vec3::Y * 0.5,
+                scale: Vec3::new(ARENA_SIZE, 1.0, ARENA_SIZE),
+                ..Default::default()
+            },
+        );
+        let (player, _) = spawn_character(&mut commands, ARENA + START, MovementBasis::World);
+        queue.apply(&mut app.world);
+
+        let mut harness = Self { app, player, time };
+        harness.run(SETTLE_TICKS);
+        harness
+    }
+
+    // `transform` is relative to where the character started
+    pub fn spawn_block(&mut self, block: Block, mut transform: Transform) {
+        transform.translation += ARENA + START;
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &self.app.world);
+        commands.spawn_block(self.app.world.resource::<MyAssets>(), block, transform);
+        queue.apply(&mut self.app.world);
+    }
+
+    pub fn capture(&mut self) -> PhysicsSnapshot {
+        PhysicsSnapshot::capture(&mut self.app.world)
+    }
+
+    pub fn restore(&mut self, snapshot: &PhysicsSnapshot) {
+        snapshot.restore(&mut self.app.world);
+    }
+
+    // Raw bits of every body's transform and velocity, ordered by entity
+    pub fn body_bits(&mut self) -> Vec<(Entity, Vec<u32>)> {
+        let mut body_q = self
+            .app
+            .world
+            .query_filtered::<(Entity, &Transform, Option<&Velocity>), With<RigidBody>>();
+        let mut bodies: Vec<_> = body_q
+            .iter(&self.app.world)
+            .map(|(entity, transform, velocity)| {
+                let velocity = velocity.copied().unwrap_or_default();
+                let bits = [
+                    transform.translation.to_array().as_slice(),
+                    &transform.rotation.to_array(),
+                    &velocity.linvel.to_array(),
+                    &velocity.angvel.to_array(),
+                ]
+                .concat()
+                .into_iter()
+                .map(f32::to_bits)
+                .collect();
+                (entity, bits)
+            })
+            .collect();
+        bodies.sort_by_key(|(entity, _)| *entity);
+        bodies
+    }
+
+    pub fn tuning(&self) -> &ControllerTuning {
+        self.app.world.resource::<ControllerTuning>()
+    }
+
+    pub fn tick_delta(&self) -> f32 {
+        self.app.world.resource::<PhysicsTick>().delta()
+    }
+
+    // Advances frames until exactly one more physics tick has run
+    pub fn step(&mut self) {
+        let count = self.app.world.resource::<PhysicsTick>().count();
+        while self.app.world.resource::<PhysicsTick>().count() == count {
+            self.time += FRAME;
+            self.app
+                .insert_resource(TimeUpdateStrategy::ManualInstant(self.time));
+            self.app.update();
+        }
+    }
+
+    pub fn run(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+
+    pub fn hold(&mut self, movement: Vec2) {
+        self.intent_mut().movement = movement;
+    }
+
+    pub fn set_jump(&mut self, held: bool) {
+        self.intent_mut().jump.set(held);
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.app
+            .world
+            .get::<Transform>(self.player)
+            .unwrap()
+            .translation
+    }
+
+    pub fn speed(&self) -> f32 {
+        let velocity = self.app.world.get::<Velocity>(self.player).unwrap();
+        velocity.linvel.horizontal(Vec3::Y).length()
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.app
+            .world
+            .get::<Locomotion>(self.player)
+            .unwrap()
+            .is_grounded()
+    }
+
+    fn intent_mut(&mut self) -> Mut<'_, MovementIntent> {
+        self.app
+            .world
+            .get_mut::<MovementIntent>(self.player)
+            .unwrap()
+    }
+}
+
+pub fn time_to_max_speed(block: Block) -> f32 {
+    let mut harness = FeelHarness::new(block);
+    harness.hold(Vec2::X);
+
+    // Damping takes its cut after movement sets the velocity each tick, so the
+    // speed the character holds on the ground is a little under the tuned one
+    let tuning = harness.tuning();
+    let max_speed = tuning.speed / (1.0 + harness.tick_delta() * tuning.damping);
+
+    let mut ticks = 0;
+    while ticks < MAX_TICKS && harness.speed() < max_speed * MAX_SPEED_FRACTION {
+        harness.step();
+        ticks += 1;
+    }
+
+    ticks as f32 * harness.tick_delta()
+}
+
+pub fn stopping_distance(block: Block) -> f32 {
+    let mut harness = FeelHarness::new(block);
+    harness.hold(Vec2::X);
+    harness.run(RUN_TICKS);
+
+    harness.hold(Vec2::ZERO);
+    let start = harness.position();
+
+    let mut ticks = 0;
+    while ticks < MAX_TICKS && harness.speed() > STOPPED_SPEED {
+        harness.step();
+        ticks += 1;
+    }
+
+    (harness.position() - start).horizontal(Vec3::Y).length()
+}
+
+// Apex height relative to the tuned jump height and seconds in the air for a
+// single tap of jump from rest
+pub fn jump(block: Block) -> (f32, f32) {
+    let mut harness = FeelHarness::new(block);
+    let start = harness.position().y;
+
+    harness.set_jump(true);
+    harness.step();
+    harness.set_jump(false);
+
+    let mut apex = start;
+    let mut airborne = 0;
+    let mut ticks = 0;
+    while ticks < MAX_TICKS && (airborne == 0 || !harness.is_grounded()) {
+        harness.step();
+        ticks += 1;
+
+        apex = apex.max(harness.position().y);
+        if !harness.is_grounded() {
+            airborne += 1;
+        }
+    }
+
+    (
+        (apex - start) / harness.tuning().jump_height,
+        airborne as f32 * harness.tick_delta(),
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FeelReport {
+    pub time_to_max_speed: f32,
+    pub stopping_distance_ground: f32,
+    pub stopping_distance_ice: f32,
+    pub jump_apex_ratio: f32,
+    pub air_time: f32,
+}
+
+impl FeelReport {
+    pub fn measure() -> Self {
+        let (jump_apex_ratio, air_time) = jump(Block::Ground);
+
+        Self {
+            time_to_max_speed: time_to_max_speed(Block::Ground),
+            stopping_distance_ground: stopping_distance(Block::Ground),
+            stopping_distance_ice: stopping_distance(Block::Ice),
+            jump_apex_ratio,
+            air_time,
+        }
+    }
+
+    // Returns a line per metric outside its range
+    pub fn check(&self, thresholds: &FeelThresholds) -> Vec<String> {
+        [
+            (
+                "time to max speed",
+                self.time_to_max_speed,
+                &thresholds.time_to_max_speed,
+            ),
+            (
+                "stopping distance (ground)",
+                self.stopping_distance_ground,
+                &thresholds.stopping_distance_ground,
+            ),
+            (
+                "stopping distance (ice)",
+                self.stopping_distance_ice,
+                &thresholds.stopping_distance_ice,
+            ),
+            (
+                "jump apex ratio",
+                self.jump_apex_ratio,
+                &thresholds.jump_apex_ratio,
+            ),
+            ("air time", self.air_time, &thresholds.air_time),
+        ]
+        .into_iter()
+        .filter(|(_, value, range)| !range.contains(value))
+        .map(|(name, value, range)| {
+            format!(
+                "{name}: {value:.3} outside {:.3}..{:.3}",
+                range.start, range.end
+            )
+        })
+        .collect()
+    }
+}
+
+impl fmt::Display for FeelReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "time to max speed:          {:.3} s",
+            self.time_to_max_speed
+        )?;
+        writeln!(
+            f,
+            "stopping distance (ground): {:.3} m",
+            self.stopping_distance_ground
+        )?;
+        writeln!(
+            f,
+            "stopping distance (ice):    {:.3} m",
+            self.stopping_distance_ice
+        )?;
+        writeln!(
+            f,
+            "jump apex:                  {:.2}x tuned height",
+            self.jump_apex_ratio
+        )?;
+        write!(f, "air time:                   {:.3} s", self.air_time)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeelThresholds {
+    pub time_to_max_speed: Range<f32>,
+    pub stopping_distance_ground: Range<f32>,
+    pub stopping_distance_ice: Range<f32>,
+    pub jump_apex_ratio: Range<f32>,
+    pub air_time: Range<f32>,
+}
+
+impl Default for FeelThresholds {
+    fn default() -> Self {
+        Self {
+            time_to_max_speed: 0.1..1.0,
+            stopping_distance_ground: 0.5..3.0,
+            stopping_distance_ice: 3.0..f32::MAX,
+            jump_apex_ratio: 0.9..1.1,
+            air_time: 1.0..2.5,
+        }
+    }
+}
+
+// Fails `cargo test` when a tuning change moves the feel outside the thresholds
+#[test]
+fn feel_within_thresholds() {
+    let report = FeelReport::measure();
+    let violations = report.check(&FeelThresholds::default());
+    assert!(
+        violations.is_empty(),
+        "{report}\n\n{}",
+        violations.join("\n")
+    );
+}
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::{Climbable, GravityField, Locomotion, LocomotionEvents, MovementIntent};
+use bevy_extensions::Vec3UpExt;
+use bevy_physics::*;
+
+use crate::{ControllerTuning, GroundState, Player};
+
+pub struct LadderPlugin;
+
+impl Plugin for LadderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(
+            PhysicsLabel::PreUpdate,
+            attach
+                .after(crate::set_ground_state)
+                .before(crate::on_ground_change),
+        )
+        .add_physics_system(PhysicsLabel::Update, climbing)
+        .add_physics_system(PhysicsLabel::Update, detach)
+        .add_snapshot_component::<Climber>();
+    }
+}
+
+#[derive(Component, Default, Clone)]
+pub struct Climber {
+    detached: bool,
+}
+
+const CLIMB_SPEED: f32 = 4.0;
+// Jumping off a ladder reaches this fraction of a regular jump
+const DETACH_JUMP_SCALE: f32 = 0.5;
+
+fn attach(
+    mut player_q: Query<(Entity, &mut Climber, &mut GroundState, &MovementIntent), With<Player>>,
+    climbable_q: Query<(), With<Climbable>>,
+    physics: Res<PhysicsContext>,
+) {
+    for (player, mut climber, mut ground_state, intent) in &mut player_q {
+        let in_volume = physics
+            .intersections_with(player)
+            .any(|(e1, e2, intersecting)| {
+                let other = if e1 == player { e2 } else { e1 };
+                intersecting && climbable_q.contains(other)
+            });
+
+        if !in_volume {
+            climber.detached = false;
+
+            if *ground_state == GroundState::Climbing {
+                *ground_state = GroundState::None;
+            }
+
+            continue;
+        }
+
+        if *ground_state != GroundState::Climbing
+            && *ground_state != GroundState::Hanging
+            && !climber.detached
+            && intent.movement.y != 0.0
+        {
+            *ground_state = GroundState::Climbing;
+        }
+    }
+}
+
+fn climbing(
+    mut player_q: Query<
+        (
+            &mut Velocity,
+            &GroundState,
+            &MovementIntent,
+            &GravityField,
+            &Transform,
+        ),
+        With<Player>,
+    >,
+) {
+    for (mut velocity, ground_state, intent, gravity_field, transform) in &mut player_q {
+        if *ground_state == GroundState::Climbing {
+            velocity.linvel =
+                gravity_field.up(transform.translation) * -intent.movement.y * CLIMB_SPEED;
+        }
+    }
+}
+
+type DetachQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Climber,
+        &'static mut GroundState,
+        &'static mut Velocity,
+        &'static mut Locomotion,
+        &'static MovementIntent,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
+
+fn detach(
+    mut player_q: DetachQuery,
+    mut events: LocomotionEvents<PhysicsTick>,
+    tuning: Res<ControllerTuning>,
+) {
+    for (
+        player,
+        mut climber,
+        mut ground_state,
+        mut velocity,
+        mut locomotion,
+        intent,
+        gravity_field,
+        transform,
+    ) in &mut player_q
+    {
+        if intent.jump.just_pressed() && *ground_state == GroundState::Climbing {
+            climber.detached = true;
+            *ground_state = GroundState::None;
+
+            // The jump is flown with airborne gravity, not the disabled climbing gravity
+            let jump_speed = tuning.jump_speed(
+                tuning.airborne.gravity,
+                tuning.jump_height * DETACH_JUMP_SCALE,
+            );
+            locomotion.jump(player, &mut events);
+            let up = gravity_field.up(transform.translation);
+            velocity.linvel = velocity.linvel.with_vertical(up, jump_speed);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::{GravityField, InputDirection, MovementIntent};
+use bevy_extensions::*;
+use bevy_physics::*;
+
+use crate::{crouch::CAPSULE_RADIUS, GroundState, Player};
+
+pub struct LedgePlugin;
+
+impl Plugin for LedgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(
+            PhysicsLabel::PreUpdate,
+            grab_ledge
+                .after(crate::set_ground_state)
+                .before(crate::on_ground_change),
+        )
+        .add_physics_system(PhysicsLabel::Update, hang)
+        // A mantle starts moving on the tick after the jump, once letting go and
+        // shimmying have been handled
+        .add_physics_system(PhysicsLabel::Update, climb.after(hang))
+        .add_snapshot_component::<LedgeGrab>();
+    }
+}
+
+#[derive(Component, Default, Clone)]
+pub enum LedgeGrab {
+    #[default]
+    None,
+    Hanging {
+        normal: Vec3,
+    },
+    Mantling {
+        start: Vec3,
+        end: Vec3,
+        ticks: u32,
+    },
+}
+
+const LEDGE_INSET: f32 = 0.1;
+const GRAB_MIN_HEIGHT: f32 = 1.2;
+const GRAB_MAX_HEIGHT: f32 = 2.4;
+const HANG_HEIGHT: f32 = 1.8;
+const SHIMMY_SPEED: f32 = 3.0;
+const MANTLE_TICKS: u32 = 6;
+
+struct Ledge {
+    top: Vec3,
+    normal: Vec3,
+    up: Vec3,
+}
+
+impl Ledge {
+    fn hang_position(&self) -> Vec3 {
+        self.top + self.normal * (CAPSULE_RADIUS + LEDGE_INSET) - self.up * HANG_HEIGHT
+    }
+
+    fn mantle_position(&self) -> Vec3 {
+        self.top - self.normal * CAPSULE_RADIUS
+    }
+}
+
+fn find_ledge(
+    player: Entity,
+    position: Vec3,
+    direction: Vec3,
+    up: Vec3,
+    physics: &PhysicsContext,
+) -> Option<Ledge> {
+    let filter = QueryFilter {
+        flags: QueryFilterFlags::EXCLUDE_SENSORS,
+        exclude_rigid_body: Some(player),
+        ..Default::default()
+    };
+
+    // Forward cast at chest height to find the wall.
+    let (_, wall) = physics.cast_ray_and_get_normal(
+        position + up * GRAB_MIN_HEIGHT,
+        direction,
+        CAPSULE_RADIUS + 0.3,
+        true,
+        filter,
+    )?;
+
+    if wall.normal.vertical(up).abs() > 0.3 {
+        return None;
+    }
+
+    let normal = wall.normal.horizontal(up).normalize();
+
+    // Downward cast from above the edge to find the top surface.
+    let origin = (wall.point - normal * LEDGE_INSET)
+        .with_vertical(up, position.vertical(up) + GRAB_MAX_HEIGHT);
+    let (_, top) = physics.cast_ray_and_get_normal(
+        origin,
+        -up,
+        GRAB_MAX_HEIGHT - GRAB_MIN_HEIGHT,
+        true,
+        filter,
+    )?;
+
+    if top.toi == 0.0 || top.normal.vertical(up) < 0.7 {
+        return None;
+    }
+
+    Some(Ledge {
+        top: top.point,
+        normal,
+        up,
+    })
+}
+
+type LedgeQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut LedgeGrab,
+        &'static mut GroundState,
+        &'static mut Transform,
+        &'static mut Velocity,
+        &'static InputDirection,
+        &'static MovementIntent,
+        &'static GravityField,
+    ),
+    With<Player>,
+>;
+
+fn grab_ledge(mut player_q: LedgeQuery, physics: Res<PhysicsContext>) {
+    for (
+        player,
+        mut ledge_grab,
+        mut ground_state,
+        mut transform,
+        mut velocity,
+        input_direction,
+        intent,
+        gravity_field,
+    ) in &mut player_q
+    {
+        let up = gravity_field.up(transform.translation);
+        if *ground_state != GroundState::None
+            || input_direction.is_zero()
+            || intent.crouch.held()
+            || velocity.linvel.vertical(up) > 1.0
+        {
+            continue;
+        }
+
+        let direction = input_direction.get().horizontal(up).normalize_or_zero();
+        if let Some(ledge) = find_ledge(player, transform.translation, direction, up, &physics) {
+            transform.translation = ledge.hang_position();
+            velocity.linvel = Vec3::ZERO;
+            *ground_state = GroundState::Hanging;
+            *ledge_grab = LedgeGrab::Hanging {
+                normal: ledge.normal,
+            };
+        }
+    }
+}
+
+fn hang(mut player_q: LedgeQuery, physics: Res<PhysicsContext>, tick: Res<PhysicsTick>) {
+    for (
+        player,
+        mut ledge_grab,
+        mut ground_state,
+        mut transform,
+        mut velocity,
+        input_direction,
+        intent,
+        gravity_field,
+    ) in &mut player_q
+    {
+        let up = gravity_field.up(transform.translation);
+        match *ledge_grab {
+            LedgeGrab::None => {}
+            LedgeGrab::Hanging { normal } => {
+                velocity.linvel = Vec3::ZERO;
+
+                if intent.crouch.held() || input_direction.get().dot(normal) > 0.5 {
+                    *ledge_grab = LedgeGrab::None;
+                    *ground_state = GroundState::None;
+                    continue;
+                }
+
+                let tangent = normal.cross(up);
+                let shimmy =
+                    tangent * input_direction.get().dot(tangent) * SHIMMY_SPEED * tick.delta();
+                if shimmy == Vec3::ZERO {
+                    continue;
+                }
+
+                if let Some(ledge) = find_ledge(
+                    player,
+                    transform.translation + shimmy,
+                    -normal,
+                    up,
+                    &physics,
+                ) {
+                    transform.translation = ledge.hang_position();
+                    *ledge_grab = LedgeGrab::Hanging {
+                        normal: ledge.normal,
+                    };
+                }
+            }
+            LedgeGrab::Mantling { start, end, ticks } => {
+                velocity.linvel = Vec3::ZERO;
+
+                let ticks = ticks + 1;
+                let t = ticks as f32 / MANTLE_TICKS as f32;
+
+                // Rise along the wall first, then step forward onto the top.
+                let corner = start.with_vertical(up, end.vertical(up));
+                transform.translation = if t < 0.5 {
+                    start.lerp(corner, t * 2.0)
+                } else {
+                    corner.lerp(end, t * 2.0 - 1.0)
+                };
+
+                if ticks == MANTLE_TICKS {
+                    *ledge_grab = LedgeGrab::None;
+                    *ground_state = GroundState::default();
+                } else {
+                    *ledge_grab = LedgeGrab::Mantling { start, end, ticks };
+                }
+            }
+        }
+    }
+}
+
+fn climb(
+    mut player_q: Query<
+        (
+            Entity,
+            &mut LedgeGrab,
+            &Transform,
+            &MovementIntent,
+            &GravityField,
+        ),
+        With<Player>,
+    >,
+    physics: Res<PhysicsContext>,
+) {
+    for (player, mut ledge_grab, transform, intent, gravity_field) in &mut player_q {
+        if !intent.jump.just_pressed() {
+            continue;
+        }
+
+        if let LedgeGrab::Hanging { normal } = *ledge_grab {
+            let up = gravity_field.up(transform.translation);
+            if let Some(ledge) = find_ledge(player, transform.translation, -normal, up, &physics) {
+                *ledge_grab = LedgeGrab::Mantling {
+                    start: transform.translation,
+                    end: ledge.mantle_position(),
+                    ticks: 0,
+                };
+            }
+        }
+    }
+}
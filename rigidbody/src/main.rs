@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 use bevy_actions::*;
@@ -5,35 +7,147 @@ use bevy_bootstrap::*;
 use bevy_extensions::*;
 use bevy_physics::*;
 
+mod ai;
+mod bounce;
+mod crouch;
+mod dash;
+mod debug;
+#[cfg(test)]
+mod feel;
+mod ladder;
+mod ledge;
+mod net;
+#[cfg(test)]
+mod rollback;
+mod skate;
+mod sprint;
+mod swim;
+mod transport;
+mod tuning;
+
+use crouch::Stance;
+use ladder::Climber;
+use ledge::LedgeGrab;
+use skate::Skater;
+use sprint::{Sprint, Stamina};
+use swim::Swimmer;
+use tuning::ControllerTuning;
+
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
-        .add_plugin(PhysicsPlugin)
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        watch_for_changes: true,
+        ..Default::default()
+    }));
+    simulation(&mut app);
+
+    match net::local_args().map(|(conditions, port)| net::bind_local(conditions, port)) {
+        Some(Ok((server, bot, client))) => {
+            headless(move |app| {
+                app.insert_resource(server).add_plugin(net::ServerPlugin);
+            });
+            headless(move |app| {
+                app.insert_resource(bot).add_plugin(net::ClientPlugin);
+            });
+            app.insert_resource(client).add_plugin(net::ClientPlugin);
+        }
+        Some(Err(err)) => {
+            error!("local network session failed to start, playing offline: {err}");
+            app.add_startup_system(setup);
+        }
+        None => {
+            app.add_startup_system(setup);
+        }
+    }
+
+    app.run();
+}
+
+// Runs another simulation on its own thread without a window
+fn headless(configure: impl FnOnce(&mut App) + Send + 'static) {
+    std::thread::spawn(move || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(HeadlessPlugins);
+        simulation(&mut app);
+        configure(&mut app);
+
+        loop {
+            app.update();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    });
+}
+
+fn simulation(app: &mut App) {
+    app.add_plugin(PhysicsPlugin)
         .add_plugin(ActionsPlugin)
         .add_plugin(BootstrapPlugin)
-        .add_startup_system(setup)
-        .add_system_set_to_stage(
-            CoreStage::Update,
-            SystemSet::new().with_system(rotation).with_system(jump),
-        )
+        .add_plugin(ai::AiPlugin)
+        .add_plugin(crouch::CrouchPlugin)
+        .add_plugin(bounce::BouncePlugin)
+        .add_plugin(sprint::SprintPlugin)
+        .add_plugin(dash::DashPlugin)
+        .add_plugin(ledge::LedgePlugin)
+        .add_plugin(ladder::LadderPlugin)
+        .add_plugin(swim::SwimPlugin)
+        .add_plugin(skate::SkatePlugin)
+        .add_plugin(debug::DebugPlugin)
+        .add_plugin(TuningPlugin::<ControllerTuning>::new(tuning::TUNING_PATH))
+        .insert_resource(GroundTransitions::default())
+        .add_snapshot_component::<GroundState>()
+        .add_snapshot_component::<ScalarBlend>()
+        .add_snapshot_component::<SpeedScale>()
+        .add_snapshot_component::<AccelerationScale>()
+        .add_snapshot_component::<DampingScale>()
+        .add_snapshot_component::<JumpHeightScale>()
+        .add_system_to_stage(CoreStage::Update, rotation)
         .add_physics_system_set(
             PhysicsLabel::PreUpdate,
             SystemSet::new()
-                .with_system(set_ground_state)
-                .with_system(on_ground_change.after(set_ground_state)),
+                .with_system(sync_gravity)
+                .with_system(align_to_gravity)
+                .with_system(apply_surface_tuning)
+                .with_system(
+                    set_ground_state
+                        .after(align_to_gravity)
+                        .after(apply_surface_tuning),
+                )
+                .with_system(on_ground_change.after(set_ground_state))
+                .with_system(blend_scalars.after(on_ground_change))
+                .with_system(
+                    update_locomotion::<GroundState, Velocity, PhysicsTick>.after(on_ground_change),
+                ),
         )
-        .add_physics_system_set(PhysicsLabel::Update, SystemSet::new().with_system(movement))
         .add_physics_system_set(
-            PhysicsLabel::PostUpdate,
-            SystemSet::new().with_system(apply_physics_scalars),
+            PhysicsLabel::Update,
+            SystemSet::new().with_system(movement).with_system(jump),
         )
-        .run();
+        .add_physics_system_set(
+            PhysicsLabel::PostUpdate,
+            SystemSet::new()
+                .with_system(apply_physics_scalars)
+                .with_system(apply_gravity_field),
+        );
 }
 
 #[derive(Bundle)]
 struct PlayerBundle {
     marker: Player,
     ground_state: GroundState,
+    stance: Stance,
+    sprint: Sprint,
+    stamina: Stamina,
+    dash: Dash,
+    ledge_grab: LedgeGrab,
+    climber: Climber,
+    swimmer: Swimmer,
+    skater: Skater,
+    movement_intent: MovementIntent,
+    movement_basis: MovementBasis,
+    input_direction: InputDirection,
+    gravity_field: GravityField,
+    locomotion: Locomotion,
+    scalar_blend: ScalarBlend,
     speed_scale: SpeedScale,
     acceleration_scale: AccelerationScale,
     damping_scale: DampingScale,
@@ -44,83 +158,126 @@ struct PlayerBundle {
 #[derive(Component)]
 struct Player;
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 struct SpeedScale(f32);
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 struct AccelerationScale(f32);
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 struct DampingScale(f32);
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 struct JumpHeightScale(f32);
 
-#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 enum GroundState {
     None,
-    #[default]
-    Normal,
-    Slippery,
+    Ground(SurfaceMaterial),
+    Hanging,
+    Climbing,
+    Swimming,
 }
 
-struct Scalars {
-    speed: f32,
-    acceleration: f32,
-    damping: f32,
-    gravity: f32,
-    jump_height: f32,
+impl Default for GroundState {
+    fn default() -> Self {
+        Self::Ground(SurfaceMaterial::default())
+    }
 }
 
-const BASE_SPEED: f32 = 10.0;
-const BASE_ACCELERATION: f32 = BASE_SPEED * 3.0;
-const BASE_DAMPING: f32 = 1.0;
-const BASE_JUMP_HEIGHT: f32 = 3.0;
+impl LocomotionGround for GroundState {
+    fn footing(&self) -> Footing {
+        match self {
+            GroundState::None => Footing::Air,
+            GroundState::Ground(_) => Footing::Ground,
+            GroundState::Hanging => Footing::Ledge,
+            GroundState::Climbing => Footing::Ladder,
+            GroundState::Swimming => Footing::Water,
+        }
+    }
+}
 
 impl GroundState {
-    fn scalars(&self) -> Scalars {
+    fn mode(&self) -> MovementMode {
         match self {
-            GroundState::None => Scalars {
-                speed: 1.0,
-                acceleration: 0.2,
-                damping: 0.1,
-                gravity: 1.1,
-                jump_height: 1.0,
-            },
-            GroundState::Normal => Scalars {
-                speed: 1.0,
-                acceleration: 1.0,
-                damping: 2.0,
-                gravity: 1.0,
-                jump_height: 1.0,
-            },
-            GroundState::Slippery => Scalars {
-                speed: 1.5,
-                acceleration: 0.2,
-                damping: 0.0,
-                gravity: 1.0,
-                jump_height: 1.0,
-            },
+            GroundState::Ground(surface) => surface.mode,
+            _ => MovementMode::Free,
+        }
+    }
+
+    fn belt_velocity(&self) -> Vec3 {
+        match self {
+            GroundState::Ground(surface) => surface.velocity,
+            _ => Vec3::ZERO,
+        }
+    }
+
+    fn scalars(&self, tuning: &ControllerTuning) -> Scalars {
+        match self {
+            GroundState::None => tuning.airborne,
+            GroundState::Ground(surface) => surface.scalars(),
+            GroundState::Hanging => tuning.hanging,
+            GroundState::Climbing => tuning.climbing,
+            GroundState::Swimming => tuning.swimming,
         }
     }
 }
 
+const WANDER_SEED: u64 = 0;
+
 fn setup(mut commands: Commands) {
     // Player
+    let (player, actor) = spawn_character(&mut commands, Vec3::ZERO, MovementBasis::Camera);
+    commands.entity(player).insert(LocallyControlled);
+
+    // Camera follow
+    commands.camera_follow(actor);
+
+    // Wanderer
+    let (wanderer, _) = spawn_character(
+        &mut commands,
+        Vec3::new(4.0, 0.0, 4.0),
+        MovementBasis::World,
+    );
+    commands
+        .entity(wanderer)
+        .insert(ai::Wander::new(WANDER_SEED));
+}
+
+fn spawn_character(
+    commands: &mut Commands,
+    translation: Vec3,
+    movement_basis: MovementBasis,
+) -> (Entity, Entity) {
     let player = commands
         .spawn((
-            TransformBundle::default(),
+            TransformBundle::from(Transform::from_translation(translation)),
             PlayerBundle {
                 marker: Player,
-                ground_state: GroundState::Normal,
+                ground_state: GroundState::default(),
+                stance: Stance::Standing,
+                sprint: Sprint::default(),
+                stamina: Stamina::default(),
+                dash: Dash::default(),
+                ledge_grab: LedgeGrab::default(),
+                climber: Climber::default(),
+                swimmer: Swimmer::default(),
+                skater: Skater::default(),
+                movement_intent: MovementIntent::default(),
+                movement_basis,
+                input_direction: InputDirection::default(),
+                gravity_field: GravityField::default(),
+                locomotion: Locomotion::default(),
+                scalar_blend: ScalarBlend::new(SurfaceMaterial::default().scalars()),
                 speed_scale: SpeedScale(1.0),
                 acceleration_scale: AccelerationScale(1.0),
                 damping_scale: DampingScale(1.0),
                 gravity_scale: GravityScale(1.0),
                 jump_height_scale: JumpHeightScale(1.0),
             },
+            GroundProbe,
             RigidBody::Dynamic,
-            Collider::capsule((Vec3::Y * 0.5).into(), (Vec3::Y * 1.5).into(), 0.5),
+            Stance::Standing.collider(),
             CollisionGroups::from(PhysicsLayer::PLAYER),
             Friction {
                 coefficient: 1.0,
@@ -149,69 +306,135 @@ fn setup(mut commands: Commands) {
         rotate: false,
     });
 
-    // Camera follow
-    commands.camera_follow(actor);
+    (player, actor)
 }
 
+// Rapier pulls every body along -Y, so its gravity follows the tuning as well
+fn sync_gravity(mut config: ResMut<PhysicsConfiguration>, tuning: Res<ControllerTuning>) {
+    if tuning.is_changed() {
+        config.gravity = -Vec3::Y * tuning.gravity;
+    }
+}
+
+fn align_to_gravity(mut player_q: Query<(&mut Transform, &GravityField), With<Player>>) {
+    for (mut transform, gravity_field) in &mut player_q {
+        let rotation = Quat::from_rotation_arc(Vec3::Y, gravity_field.up(transform.translation));
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+// Named blocks take their materials from the tuning, including blocks spawned
+// after it last changed
+fn apply_surface_tuning(
+    mut block_q: Query<(
+        &SurfaceName,
+        ChangeTrackers<SurfaceName>,
+        &mut SurfaceMaterial,
+    )>,
+    tuning: Res<ControllerTuning>,
+) {
+    for (name, name_tracker, mut surface) in &mut block_q {
+        if !tuning.is_changed() && !name_tracker.is_changed() {
+            continue;
+        }
+
+        match tuning.surfaces.get(&**name) {
+            Some(tuned) if *surface != *tuned => *surface = *tuned,
+            Some(_) => {}
+            None => warn!("no tuned surface named {}", **name),
+        }
+    }
+}
+
+// Cast down from slightly above the feet
+const GROUND_RAY_OFFSET: f32 = 0.1;
+const GROUND_RAY_LENGTH: f32 = 0.2;
+
 fn set_ground_state(
-    mut player_q: Query<(Entity, &mut GroundState, &Transform), With<Player>>,
-    block_q: Query<&Block>,
+    mut player_q: Query<(Entity, &mut GroundState, &Transform, &GravityField), With<Player>>,
+    surface_q: Query<&SurfaceMaterial>,
     physics: Res<PhysicsContext>,
 ) {
-    let (player, mut ground_state, transform) = player_q.single_mut();
-
-    let ray_hit = physics.cast_ray(
-        transform.translation + Vec3::Y * 0.1,
-        -Vec3::Y,
-        0.2,
-        true,
-        QueryFilter {
-            exclude_rigid_body: Some(player),
-            ..Default::default()
-        },
-    );
+    for (player, mut ground_state, transform, gravity_field) in &mut player_q {
+        if let GroundState::Hanging | GroundState::Climbing | GroundState::Swimming = *ground_state
+        {
+            continue;
+        }
 
-    let state = if let Some((hit_entity, _)) = ray_hit {
-        if let Ok(block) = block_q.get(hit_entity) {
-            match block {
-                Block::Ice => GroundState::Slippery,
-                _ => GroundState::Normal,
-            }
+        let up = gravity_field.up(transform.translation);
+        let ray_hit = physics.cast_ray(
+            transform.translation + up * GROUND_RAY_OFFSET,
+            -up,
+            GROUND_RAY_LENGTH,
+            true,
+            QueryFilter {
+                flags: QueryFilterFlags::EXCLUDE_SENSORS,
+                exclude_rigid_body: Some(player),
+                ..Default::default()
+            },
+        );
+
+        let state = if let Some((hit_entity, _)) = ray_hit {
+            GroundState::Ground(surface_q.get(hit_entity).copied().unwrap_or_default())
         } else {
-            GroundState::Normal
-        }
-    } else {
-        GroundState::None
-    };
+            GroundState::None
+        };
 
-    if *ground_state != state {
-        *ground_state = state;
+        if *ground_state != state {
+            *ground_state = state;
+        }
     }
 }
 
+// Reloaded tuning restarts the blend towards the current state's new scalars
 fn on_ground_change(
     mut player_q: Query<
-        (
-            &GroundState,
-            &mut SpeedScale,
-            &mut AccelerationScale,
-            &mut DampingScale,
-            &mut GravityScale,
-            &mut JumpHeightScale,
-        ),
-        (Changed<GroundState>, With<Player>),
+        (&GroundState, ChangeTrackers<GroundState>, &mut ScalarBlend),
+        With<Player>,
     >,
+    transitions: Res<GroundTransitions>,
+    tuning: Res<ControllerTuning>,
 ) {
-    if let Ok((
-        ground_state,
+    for (ground_state, ground_state_tracker, mut scalar_blend) in &mut player_q {
+        if !ground_state_tracker.is_changed() && !tuning.is_changed() {
+            continue;
+        }
+
+        scalar_blend.start(
+            ground_state.scalars(&tuning),
+            *ground_state == GroundState::None,
+            &transitions,
+        );
+    }
+}
+
+type BlendQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut ScalarBlend,
+        &'static mut SpeedScale,
+        &'static mut AccelerationScale,
+        &'static mut DampingScale,
+        &'static mut GravityScale,
+        &'static mut JumpHeightScale,
+    ),
+    With<Player>,
+>;
+
+fn blend_scalars(mut player_q: BlendQuery, tick: Res<PhysicsTick>) {
+    for (
+        mut scalar_blend,
         mut speed_scale,
         mut acceleration_scale,
         mut damping_scale,
         mut gravity_scale,
         mut jump_height_scale,
-    )) = player_q.get_single_mut()
+    ) in &mut player_q
     {
-        let scalars = ground_state.scalars();
+        let scalars = scalar_blend.step(tick.delta());
         speed_scale.0 = scalars.speed;
         acceleration_scale.0 = scalars.acceleration;
         damping_scale.0 = scalars.damping;
@@ -220,62 +443,211 @@ fn on_ground_change(
     }
 }
 
-fn movement(
-    mut player_q: Query<
-        (&mut Velocity, &GroundState, &SpeedScale, &AccelerationScale),
-        With<Player>,
-    >,
-    input: Res<InputMovement>,
-    tick: Res<PhysicsTick>,
-) {
-    let (mut velocity, _ground_state, speed_scale, acceleration_scale) = player_q.single_mut();
+type MovementQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Velocity,
+        &'static GroundState,
+        &'static Stance,
+        &'static Sprint,
+        &'static SpeedScale,
+        &'static AccelerationScale,
+        &'static Dash,
+        &'static InputDirection,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
 
-    if input.is_zero() {
-        return;
-    }
+fn movement(mut player_q: MovementQuery, tuning: Res<ControllerTuning>, tick: Res<PhysicsTick>) {
+    for (
+        mut velocity,
+        ground_state,
+        stance,
+        sprint,
+        speed_scale,
+        acceleration_scale,
+        dash,
+        input_direction,
+        gravity_field,
+        transform,
+    ) in &mut player_q
+    {
+        let belt_velocity = ground_state.belt_velocity();
 
-    let direction = input.x0z();
-    let current_velocity = velocity.linvel.x0z();
-    let target_velocity = direction * BASE_SPEED * speed_scale.0;
-    let max_delta = BASE_ACCELERATION * acceleration_scale.0 * tick.delta();
+        if (input_direction.is_zero() && belt_velocity == Vec3::ZERO)
+            || dash.is_active()
+            || matches!(ground_state, GroundState::Climbing | GroundState::Swimming)
+            || ground_state.mode() != MovementMode::Free
+        {
+            continue;
+        }
 
-    velocity.linvel = current_velocity
-        .move_towards(target_velocity, max_delta)
-        .x_z(velocity.linvel.y);
+        let up = gravity_field.up(transform.translation);
+        let direction = input_direction.get().horizontal(up).normalize_or_zero();
+        let current_velocity = velocity.linvel.horizontal(up);
+        let target_velocity =
+            direction * tuning.speed * speed_scale.0 * stance.speed() * sprint.speed()
+                + belt_velocity;
+        let max_delta = tuning.acceleration * acceleration_scale.0 * tick.delta();
+
+        velocity.linvel = current_velocity
+            .move_towards(target_velocity, max_delta)
+            .with_vertical(up, velocity.linvel.vertical(up));
+    }
 }
 
+type RotationQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static InputDirection,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    (With<Player>, Without<Actor>),
+>;
+
 fn rotation(
-    mut player_q: Query<&mut Transform, With<Actor>>,
-    input: Res<InputMovement>,
+    mut actor_q: Query<(&mut Transform, &PhysicsInterpolation), With<Actor>>,
+    player_q: RotationQuery,
+    tuning: Res<ControllerTuning>,
     time: Res<Time>,
 ) {
-    if input.is_zero() {
-        return;
-    }
+    for (mut transform, interpolation) in &mut actor_q {
+        let (input_direction, gravity_field, player_transform) =
+            match player_q.get(interpolation.target) {
+                Ok(player) => player,
+                Err(_) => continue,
+            };
+        let up = gravity_field.up(player_transform.translation);
 
-    const ROTATION_SPEED: f32 = 15.0;
+        // Without input the actor keeps its heading but still follows a changing up vector.
+        let direction = if input_direction.is_zero() {
+            transform.forward()
+        } else {
+            input_direction.get()
+        }
+        .horizontal(up);
 
-    let mut transform = player_q.single_mut();
-    transform.rotation = Quat::slerp(
-        transform.rotation,
-        Quat::from_look(input.x0z(), Vec3::Y),
-        ROTATION_SPEED * time.delta_seconds(),
-    );
+        if direction == Vec3::ZERO {
+            continue;
+        }
+
+        transform.rotation = Quat::slerp(
+            transform.rotation,
+            Quat::from_look(direction, up),
+            tuning.rotation_speed * time.delta_seconds(),
+        );
+    }
 }
 
+type JumpQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Velocity,
+        &'static mut Locomotion,
+        &'static JumpHeightScale,
+        &'static GravityField,
+        &'static Transform,
+        &'static MovementIntent,
+    ),
+    With<Player>,
+>;
+
 fn jump(
-    mut player_q: Query<(&mut ExternalImpulse, &GravityScale, &JumpHeightScale), With<Player>>,
-    input_action: Res<InputAction>,
+    mut player_q: JumpQuery,
+    mut events: LocomotionEvents<PhysicsTick>,
+    tuning: Res<ControllerTuning>,
 ) {
-    if let InputAction::Jump = *input_action {
-        let (mut impulse, gravity_scale, jump_height_scale) = player_q.single_mut();
-        impulse.impulse.y +=
-            f32::sqrt(2.0 * 9.81 * gravity_scale.0 * BASE_JUMP_HEIGHT * jump_height_scale.0);
+    for (
+        player,
+        mut velocity,
+        mut locomotion,
+        jump_height_scale,
+        gravity_field,
+        transform,
+        intent,
+    ) in &mut player_q
+    {
+        if !intent.jump.just_pressed() {
+            continue;
+        }
+
+        // States that can't jump scale the height down to nothing. The jump is flown
+        // with airborne gravity, and setting the velocity keeps it independent of
+        // the body's mass.
+        let jump_speed = tuning.jump_speed(
+            tuning.airborne.gravity,
+            tuning.jump_height * jump_height_scale.0,
+        );
+        if jump_speed > 0.0 {
+            locomotion.jump(player, &mut events);
+            let up = gravity_field.up(transform.translation);
+            velocity.linvel = velocity.linvel.with_vertical(up, jump_speed);
+        }
     }
 }
 
-fn apply_physics_scalars(mut player_q: Query<(&mut Damping, &DampingScale), With<Player>>) {
-    let (mut damping, damping_scale) = player_q.single_mut();
+type ScalarsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Damping,
+        &'static mut Velocity,
+        &'static GroundState,
+        &'static DampingScale,
+        &'static Dash,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
 
-    damping.linear_damping = BASE_DAMPING * damping_scale.0;
+fn apply_physics_scalars(
+    mut player_q: ScalarsQuery,
+    tuning: Res<ControllerTuning>,
+    tick: Res<PhysicsTick>,
+) {
+    for (mut damping, mut velocity, ground_state, damping_scale, dash, gravity_field, transform) in
+        &mut player_q
+    {
+        let linear_damping = if dash.is_active() {
+            0.0
+        } else {
+            tuning.damping * damping_scale.0
+        };
+
+        match ground_state {
+            // Water drags in every direction
+            GroundState::Swimming => damping.linear_damping = linear_damping,
+            // Elsewhere damping stands in for friction and air control, so it only
+            // slows movement across `up` and leaves the vertical speed of a jump
+            // alone. Applied the way rapier does it.
+            _ => {
+                damping.linear_damping = 0.0;
+
+                let up = gravity_field.up(transform.translation);
+                let horizontal =
+                    velocity.linvel.horizontal(up) / (1.0 + tick.delta() * linear_damping);
+                velocity.linvel = horizontal.with_vertical(up, velocity.linvel.vertical(up));
+            }
+        }
+    }
+}
+
+fn apply_gravity_field(
+    mut player_q: Query<(&mut Velocity, &GravityField, &GravityScale, &Transform), With<Player>>,
+    tuning: Res<ControllerTuning>,
+    tick: Res<PhysicsTick>,
+) {
+    for (mut velocity, gravity_field, gravity_scale, transform) in &mut player_q {
+        // Rapier already pulls along -Y, so only the difference to the field is added.
+        let correction = gravity_field.direction(transform.translation) + Vec3::Y;
+        velocity.linvel += correction * tuning.gravity * gravity_scale.0 * tick.delta();
+    }
 }
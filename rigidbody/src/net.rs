@@ -0,0 +1,716 @@
+use std::{
+    collections::VecDeque,
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use bevy_actions::*;
+use bevy_bootstrap::*;
+use bevy_physics::*;
+
+use crate::{ai, crouch::Stance, spawn_character, transport::*};
+
+pub const DEFAULT_PORT: u16 = 7777;
+
+const HELLO_INTERVAL: f32 = 1.0;
+const INPUT_REDUNDANCY: usize = 8;
+const INPUT_BUFFER: usize = 4;
+const HISTORY_LENGTH: usize = 64;
+const CORRECTION_THRESHOLD: f32 = 0.01;
+
+// `--net-local [latency ms] [loss] [port]` runs a server and a bot client next
+// to this one
+pub fn local_args() -> Option<(LinkConditions, u16)> {
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|arg| arg == "--net-local")?;
+    let latency = args.get(index + 1).and_then(|arg| arg.parse().ok());
+    let loss = args.get(index + 2).and_then(|arg| arg.parse().ok());
+    let port = args.get(index + 3).and_then(|arg| arg.parse().ok());
+
+    let conditions = LinkConditions {
+        latency: Duration::from_millis(latency.unwrap_or(0)),
+        loss: loss.unwrap_or(0.0),
+        seed: fastrand::u64(..),
+    };
+    Some((conditions, port.unwrap_or(DEFAULT_PORT)))
+}
+
+// Binds a server on `port` and the two clients that connect to it
+pub fn bind_local(
+    conditions: LinkConditions,
+    port: u16,
+) -> io::Result<(NetServer, NetClient, NetClient)> {
+    let server = NetServer::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)), conditions)?;
+    let addr = server.local_addr()?;
+    let bot = NetClient::connect(addr, conditions, true)?;
+    let client = NetClient::connect(addr, conditions, false)?;
+    Ok((server, bot, client))
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetId(pub u8);
+
+// The local character, simulated ahead of the server
+#[derive(Component)]
+pub struct Predicted;
+
+// Another client's character, placed where the server last saw it
+#[derive(Component)]
+pub struct Proxy;
+
+fn spawn_point(id: u8) -> Vec3 {
+    Vec3::new(id as f32 * 2.0, 0.0, 0.0)
+}
+
+// Kinematic blocks from the level, which every peer spawns in the same order
+fn platforms(world: &mut World) -> Vec<Entity> {
+    let mut platform_q = world.query_filtered::<(Entity, &RigidBody), With<Block>>();
+    let mut platforms: Vec<Entity> = platform_q
+        .iter(world)
+        .filter(|(_, body)| {
+            matches!(
+                body,
+                RigidBody::KinematicPositionBased | RigidBody::KinematicVelocityBased
+            )
+        })
+        .map(|(platform, _)| platform)
+        .collect();
+    platforms.sort();
+    platforms
+}
+
+fn platform_states(world: &mut World) -> Vec<PlatformState> {
+    let plans = plan_states(world);
+
+    platforms(world)
+        .into_iter()
+        .map(|platform| {
+            let transform = world
+                .get::<Transform>(platform)
+                .copied()
+                .unwrap_or_default();
+            PlatformState {
+                position: transform.translation,
+                rotation: transform.rotation,
+                plan: plans
+                    .iter()
+                    .find(|(agent, _)| *agent == platform)
+                    .map(|(_, plan)| *plan),
+            }
+        })
+        .collect()
+}
+
+fn set_platform_states(world: &mut World, states: &[PlatformState]) {
+    for (platform, state) in platforms(world).into_iter().zip(states) {
+        if let Some(mut transform) = world.get_mut::<Transform>(platform) {
+            transform.translation = state.position;
+            transform.rotation = state.rotation;
+        }
+        if let Some(plan) = state.plan {
+            set_plan_state(world, platform, plan);
+        }
+    }
+}
+
+fn platforms_diverged(predicted: &[PlatformState], server: &[PlatformState]) -> bool {
+    predicted.iter().zip(server).any(|(predicted, server)| {
+        predicted.position.distance(server.position) > CORRECTION_THRESHOLD
+            || predicted.rotation.angle_between(server.rotation) > CORRECTION_THRESHOLD
+            || predicted.plan.map(|plan| plan.current) != server.plan.map(|plan| plan.current)
+    })
+}
+
+// Needs a `NetServer` resource, bound by the caller so it can handle the error
+pub struct ServerPlugin;
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PreUpdate, server_receive)
+            .add_physics_system(PhysicsLabel::Input, server_platforms)
+            .add_physics_system(PhysicsLabel::Input, server_tick.after(server_platforms))
+            .add_system_to_stage(CoreStage::Last, server_flush);
+    }
+}
+
+#[derive(Resource)]
+pub struct NetServer {
+    link: Link,
+    remotes: Vec<Remote>,
+    platforms: Vec<PlatformState>,
+}
+
+impl NetServer {
+    pub fn bind(addr: SocketAddr, conditions: LinkConditions) -> io::Result<Self> {
+        Ok(Self {
+            link: Link::bind(addr, conditions)?,
+            remotes: Vec::new(),
+            platforms: Vec::new(),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.link.local_addr()
+    }
+}
+
+struct Remote {
+    addr: SocketAddr,
+    id: u8,
+    entity: Entity,
+    inputs: VecDeque<InputFrame>,
+    last: Option<InputFrame>,
+    ack: u64,
+}
+
+impl Remote {
+    fn buffer(&mut self, input: InputFrame) {
+        let newest = self.inputs.back().map_or(self.ack, |input| input.tick);
+        if input.tick > newest {
+            self.inputs.push_back(input);
+        }
+    }
+
+    // Applies one buffered input per tick, repeating the last one held when
+    // the client falls behind
+    fn next(&mut self) -> Option<InputFrame> {
+        while self.inputs.len() > INPUT_BUFFER {
+            self.inputs.pop_front();
+        }
+
+        let input = match self.inputs.pop_front() {
+            Some(input) => {
+                self.ack = input.tick;
+                input
+            }
+            None => {
+                let mut input = self.last?;
+                input.intent.consume();
+                input
+            }
+        };
+
+        self.last = Some(input);
+        Some(input)
+    }
+}
+
+fn server_receive(mut server: ResMut<NetServer>, mut commands: Commands, time: Res<Time>) {
+    let server = &mut *server;
+
+    for (from, packet) in server.link.receive() {
+        match packet {
+            Packet::Hello => {
+                let id = match server.remotes.iter().find(|remote| remote.addr == from) {
+                    Some(remote) => remote.id,
+                    None => {
+                        let id = server.remotes.len() as u8;
+                        let (player, _) =
+                            spawn_character(&mut commands, spawn_point(id), MovementBasis::World);
+                        commands.entity(player).insert(NetId(id));
+
+                        server.remotes.push(Remote {
+                            addr: from,
+                            id,
+                            entity: player,
+                            inputs: VecDeque::new(),
+                            last: None,
+                            ack: 0,
+                        });
+                        id
+                    }
+                };
+
+                server
+                    .link
+                    .send(from, &Packet::Welcome { id }, time.elapsed());
+            }
+            Packet::Input { inputs } => {
+                if let Some(remote) = server.remotes.iter_mut().find(|remote| remote.addr == from) {
+                    for input in inputs {
+                        remote.buffer(input);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn server_platforms(world: &mut World) {
+    let platforms = platform_states(world);
+    world.resource_mut::<NetServer>().platforms = platforms;
+}
+
+// The state sent at the start of a tick is the result of the input acked with it
+fn server_tick(
+    mut server: ResMut<NetServer>,
+    mut player_q: Query<(
+        &NetId,
+        &Transform,
+        &Velocity,
+        &mut MovementIntent,
+        &mut InputDirection,
+    )>,
+    time: Res<Time>,
+) {
+    let server = &mut *server;
+
+    let characters: Vec<CharacterState> = player_q
+        .iter()
+        .map(|(id, transform, velocity, ..)| CharacterState {
+            id: id.0,
+            position: transform.translation,
+            velocity: velocity.linvel,
+        })
+        .collect();
+
+    for remote in &mut server.remotes {
+        server.link.send(
+            remote.addr,
+            &Packet::State {
+                ack: remote.ack,
+                characters: characters.clone(),
+                platforms: server.platforms.clone(),
+            },
+            time.elapsed(),
+        );
+
+        if let Some(input) = remote.next() {
+            if let Ok((.., mut intent, mut direction)) = player_q.get_mut(remote.entity) {
+                *intent = input.intent;
+                direction.set(input.direction);
+            }
+        }
+    }
+}
+
+fn server_flush(mut server: ResMut<NetServer>, time: Res<Time>) {
+    server.link.flush(time.elapsed());
+}
+
+// Needs a `NetClient` resource, connected by the caller so it can handle the error
+pub struct ClientPlugin;
+
+impl Plugin for ClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set_to_stage(
+            CoreStage::Update,
+            SystemSet::new()
+                .with_system(client_receive)
+                .with_system(client_reconcile.after(client_receive)),
+        )
+        .add_physics_system(PhysicsLabel::Input, client_tick.after(ai::wander))
+        .add_system_to_stage(CoreStage::Last, client_flush);
+    }
+}
+
+#[derive(Resource)]
+pub struct NetClient {
+    link: Link,
+    server: SocketAddr,
+    bot: bool,
+    id: Option<u8>,
+    hello: f32,
+    history: VecDeque<History>,
+    pending: Option<Authoritative>,
+    live: Option<(MovementIntent, Vec3)>,
+    remotes: Vec<CharacterState>,
+}
+
+impl NetClient {
+    pub fn connect(server: SocketAddr, conditions: LinkConditions, bot: bool) -> io::Result<Self> {
+        Ok(Self {
+            link: Link::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)), conditions)?,
+            server,
+            bot,
+            id: None,
+            hello: 0.0,
+            history: VecDeque::new(),
+            pending: None,
+            live: None,
+            remotes: Vec::new(),
+        })
+    }
+}
+
+// Input, position and state at the start of a predicted tick
+struct History {
+    input: InputFrame,
+    position: Vec3,
+    platforms: Vec<PlatformState>,
+    snapshot: PhysicsSnapshot,
+}
+
+// What the server had at the start of the tick after `ack`
+struct Authoritative {
+    ack: u64,
+    character: CharacterState,
+    platforms: Vec<PlatformState>,
+}
+
+fn client_receive(mut client: ResMut<NetClient>, mut commands: Commands, time: Res<Time>) {
+    let client = &mut *client;
+
+    if client.id.is_none() {
+        client.hello -= time.delta_seconds();
+        if client.hello <= 0.0 {
+            client
+                .link
+                .send(client.server, &Packet::Hello, time.elapsed());
+            client.hello = HELLO_INTERVAL;
+        }
+    }
+
+    for (_, packet) in client.link.receive() {
+        match packet {
+            Packet::Welcome { id } if client.id.is_none() => {
+                client.id = Some(id);
+
+                let basis = if client.bot {
+                    MovementBasis::World
+                } else {
+                    MovementBasis::Camera
+                };
+                let (player, actor) = spawn_character(&mut commands, spawn_point(id), basis);
+                commands.entity(player).insert((NetId(id), Predicted));
+
+                if client.bot {
+                    commands
+                        .entity(player)
+                        .insert(ai::Wander::new(u64::from(id)));
+                } else {
+                    commands.entity(player).insert(LocallyControlled);
+                    commands.camera_follow(actor);
+                }
+            }
+            Packet::State {
+                ack,
+                characters,
+                platforms,
+            } => {
+                for character in characters {
+                    if Some(character.id) == client.id {
+                        if client
+                            .pending
+                            .as_ref()
+                            .map_or(true, |pending| ack > pending.ack)
+                        {
+                            client.pending = Some(Authoritative {
+                                ack,
+                                character,
+                                platforms: platforms.clone(),
+                            });
+                        }
+                        continue;
+                    }
+
+                    match client
+                        .remotes
+                        .iter_mut()
+                        .find(|remote| remote.id == character.id)
+                    {
+                        Some(remote) => *remote = character,
+                        None => {
+                            client.remotes.push(character);
+                            spawn_proxy(&mut commands, character);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn spawn_proxy(commands: &mut Commands, character: CharacterState) {
+    let proxy = commands
+        .spawn((
+            TransformBundle::from(Transform::from_translation(character.position)),
+            NetId(character.id),
+            Proxy,
+            RigidBody::KinematicPositionBased,
+            Stance::Standing.collider(),
+            CollisionGroups::from(PhysicsLayer::PLAYER),
+        ))
+        .id();
+
+    let actor = commands.spawn_actor(ActorConfig {
+        position: character.position,
+        ..Default::default()
+    });
+    commands.entity(actor).insert(PhysicsInterpolation {
+        target: proxy,
+        translate: true,
+        rotate: false,
+    });
+}
+
+// Rolls back to the acked tick and replays the inputs since when the server
+// disagrees with what was predicted for the character or the platforms
+fn client_reconcile(world: &mut World) {
+    let Authoritative {
+        ack,
+        character: state,
+        platforms,
+    } = match world.resource_mut::<NetClient>().pending.take() {
+        Some(pending) => pending,
+        None => return,
+    };
+
+    let mut player_q =
+        world.query_filtered::<(Entity, &MovementIntent, &InputDirection), With<Predicted>>();
+    let (player, intent, direction) = match player_q.get_single(world) {
+        Ok((player, intent, direction)) => (player, *intent, direction.get()),
+        Err(_) => return,
+    };
+
+    world.resource_scope(|world, mut client: Mut<NetClient>| {
+        while client
+            .history
+            .front()
+            .is_some_and(|entry| entry.input.tick <= ack)
+        {
+            client.history.pop_front();
+        }
+
+        let entry = match client.history.front() {
+            Some(entry) if entry.input.tick == ack + 1 => entry,
+            _ => return,
+        };
+
+        if entry.position.distance(state.position) <= CORRECTION_THRESHOLD
+            && !platforms_diverged(&entry.platforms, &platforms)
+        {
+            return;
+        }
+
+        let tick = world.resource::<PhysicsTick>().clone();
+        entry.snapshot.restore(world);
+
+        if let Some(mut transform) = world.get_mut::<Transform>(player) {
+            transform.translation = state.position;
+        }
+        if let Some(mut velocity) = world.get_mut::<Velocity>(player) {
+            velocity.linvel = state.velocity;
+        }
+        set_platform_states(world, &platforms);
+
+        let rewind = tick.count() - ack;
+        world.insert_resource(tick);
+        world.resource_mut::<PhysicsTick>().rewind(rewind);
+
+        client.live = Some((intent, direction));
+    });
+}
+
+fn client_tick(world: &mut World) {
+    let tick = world.resource::<PhysicsTick>().clone();
+    let now = world.resource::<Time>().elapsed();
+
+    world.resource_scope(|world, mut client: Mut<NetClient>| {
+        let mut proxy_q = world.query_filtered::<(&NetId, &mut Transform), With<Proxy>>();
+        for (id, mut transform) in proxy_q.iter_mut(world) {
+            if let Some(remote) = client.remotes.iter().find(|remote| remote.id == id.0) {
+                if transform.translation != remote.position {
+                    transform.translation = remote.position;
+                }
+            }
+        }
+
+        let replayed = if tick.is_resimulating() {
+            client
+                .history
+                .iter()
+                .find(|entry| entry.input.tick == tick.count())
+                .map(|entry| (entry.input.intent, entry.input.direction))
+        } else {
+            client.live.take()
+        };
+
+        let mut player_q = world.query_filtered::<(
+            &mut MovementIntent,
+            &mut InputDirection,
+            &Transform,
+        ), With<Predicted>>();
+        let (input, position) = match player_q.get_single_mut(world) {
+            Ok((mut intent, mut direction, transform)) => {
+                if let Some((replayed_intent, replayed_direction)) = replayed {
+                    *intent = replayed_intent;
+                    direction.set(replayed_direction);
+                }
+
+                let input = InputFrame {
+                    tick: tick.count(),
+                    intent: *intent,
+                    direction: direction.get(),
+                };
+                (input, transform.translation)
+            }
+            Err(_) => return,
+        };
+
+        let entry = History {
+            input,
+            position,
+            platforms: platform_states(world),
+            snapshot: PhysicsSnapshot::capture(world),
+        };
+        match client
+            .history
+            .iter_mut()
+            .find(|entry| entry.input.tick == input.tick)
+        {
+            Some(existing) => *existing = entry,
+            None => client.history.push_back(entry),
+        }
+
+        while client.history.len() > HISTORY_LENGTH {
+            client.history.pop_front();
+        }
+
+        if !tick.is_resimulating() {
+            let skip = client.history.len().saturating_sub(INPUT_REDUNDANCY);
+            let inputs = client
+                .history
+                .iter()
+                .skip(skip)
+                .map(|entry| entry.input)
+                .collect();
+            let server = client.server;
+            client.link.send(server, &Packet::Input { inputs }, now);
+        }
+    });
+}
+
+fn client_flush(mut client: ResMut<NetClient>, time: Res<Time>) {
+    client.link.flush(time.elapsed());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::simulation;
+
+    const CONDITIONS: LinkConditions = LinkConditions {
+        latency: Duration::from_millis(30),
+        loss: 0.2,
+        seed: 7,
+    };
+    const FRAME: Duration = Duration::from_millis(25);
+    const WANDER_FRAMES: u32 = 120;
+    const SETTLE_FRAMES: u32 = 80;
+    const TOLERANCE: f32 = 0.05;
+
+    fn headless_app(configure: impl FnOnce(&mut App)) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(HeadlessPlugins);
+        simulation(&mut app);
+        configure(&mut app);
+        app
+    }
+
+    // Every app shares one manual clock, so the ticks, the link latency and the
+    // order packets arrive in are the same on every run
+    fn run(apps: &mut [&mut App], time: &mut Instant, frames: u32) {
+        for _ in 0..frames {
+            *time += FRAME;
+            for app in apps.iter_mut() {
+                app.insert_resource(TimeUpdateStrategy::ManualInstant(*time));
+                app.update();
+            }
+        }
+    }
+
+    // Stops the wandering, and forgets the history so a rollback to a tick
+    // from before can't bring it back
+    fn stop(client: &mut App) -> u8 {
+        let mut player_q = client
+            .world
+            .query_filtered::<(Entity, &NetId, &mut MovementIntent), With<Predicted>>();
+        let (player, id, mut intent) = player_q
+            .get_single_mut(&mut client.world)
+            .expect("client never joined");
+        *intent = MovementIntent::default();
+        let id = id.0;
+
+        client.world.entity_mut(player).remove::<ai::Wander>();
+        client.world.resource_mut::<NetClient>().history.clear();
+        id
+    }
+
+    fn server_position(server: &mut App, id: u8) -> Vec3 {
+        let mut server_q = server.world.query::<(&NetId, &Transform)>();
+        server_q
+            .iter(&server.world)
+            .find(|(net_id, _)| net_id.0 == id)
+            .map(|(_, transform)| transform.translation)
+            .expect("server has no such character")
+    }
+
+    // Two bots wander over a lossy, delayed link and then stop. Once everything
+    // has settled the prediction has to agree with the server, which it only
+    // does if it was corrected for the inputs the server never got, and the
+    // other client has to show the bot where the server has it.
+    #[test]
+    fn client_reconciles_to_server_position() {
+        let server = NetServer::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)), CONDITIONS)
+            .expect("failed to bind server");
+        let addr = server.local_addr().unwrap();
+        let bot = NetClient::connect(addr, CONDITIONS, true).expect("failed to bind client");
+        let other = NetClient::connect(addr, CONDITIONS, true).expect("failed to bind client");
+
+        let mut server = headless_app(|app| {
+            app.insert_resource(server).add_plugin(ServerPlugin);
+        });
+        let mut bot = headless_app(|app| {
+            app.insert_resource(bot).add_plugin(ClientPlugin);
+        });
+        let mut other = headless_app(|app| {
+            app.insert_resource(other).add_plugin(ClientPlugin);
+        });
+
+        let mut time = Instant::now();
+        run(
+            &mut [&mut server, &mut bot, &mut other],
+            &mut time,
+            WANDER_FRAMES,
+        );
+
+        let id = stop(&mut bot);
+        stop(&mut other);
+        run(
+            &mut [&mut server, &mut bot, &mut other],
+            &mut time,
+            SETTLE_FRAMES,
+        );
+
+        let authoritative = server_position(&mut server, id);
+
+        let mut predicted_q = bot.world.query_filtered::<&Transform, With<Predicted>>();
+        let predicted = predicted_q.single(&bot.world).translation;
+        assert!(
+            predicted.distance(authoritative) < TOLERANCE,
+            "predicted {predicted}, server has {authoritative}"
+        );
+
+        let mut proxy_q = other
+            .world
+            .query_filtered::<(&NetId, &Transform), With<Proxy>>();
+        let proxy = proxy_q
+            .iter(&other.world)
+            .find(|(net_id, _)| net_id.0 == id)
+            .map(|(_, transform)| transform.translation)
+            .expect("the other client never saw the bot");
+        assert!(
+            proxy.distance(authoritative) < TOLERANCE,
+            "proxy at {proxy}, server has {authoritative}"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::{Dash, GravityField, InputDirection, MovementMode, SurfaceMaterial};
+use bevy_extensions::*;
+use bevy_physics::*;
+
+use crate::{GroundState, Player};
+
+pub struct SkatePlugin;
+
+impl Plugin for SkatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(PhysicsLabel::Update, skating)
+            .add_snapshot_component::<Skater>();
+    }
+}
+
+#[derive(Component, Default, Clone)]
+pub struct Skater {
+    heading: Option<Heading>,
+}
+
+#[derive(Clone)]
+struct Heading {
+    direction: Vec3,
+    speed: f32,
+}
+
+type SkatingQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Skater,
+        &'static mut Velocity,
+        &'static GroundState,
+        &'static Dash,
+        &'static InputDirection,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
+
+// Entering at rest still gets the skater moving once there is input
+const MIN_SKATE_SPEED: f32 = 1.0;
+
+fn skating(mut player_q: SkatingQuery, tick: Res<PhysicsTick>) {
+    for (mut skater, mut velocity, ground_state, dash, input_direction, gravity_field, transform) in
+        &mut player_q
+    {
+        let turn_rate = match ground_state {
+            GroundState::Ground(SurfaceMaterial {
+                mode: MovementMode::Forward { turn_rate },
+                ..
+            }) if !dash.is_active() => *turn_rate,
+            _ => {
+                skater.heading = None;
+                continue;
+            }
+        };
+
+        let up = gravity_field.up(transform.translation);
+        let input = input_direction.get().horizontal(up).normalize_or_zero();
+
+        // Speed and facing are locked in at the moment the surface is entered.
+        let heading = skater.heading.get_or_insert_with(|| {
+            let planar = velocity.linvel.horizontal(up);
+            Heading {
+                direction: planar.try_normalize().unwrap_or(input),
+                speed: planar.length().max(MIN_SKATE_SPEED),
+            }
+        });
+
+        // A changing up vector tilts the plane the heading has to stay in
+        heading.direction = heading.direction.horizontal(up).normalize_or_zero();
+
+        if input != Vec3::ZERO {
+            heading.direction = steer(heading.direction, input, up, turn_rate * tick.delta());
+        }
+
+        velocity.linvel =
+            (heading.direction * heading.speed).with_vertical(up, velocity.linvel.vertical(up));
+    }
+}
+
+fn steer(direction: Vec3, target: Vec3, up: Vec3, max_angle: f32) -> Vec3 {
+    if direction == Vec3::ZERO {
+        return target;
+    }
+
+    let angle = direction.angle_between(target);
+    if angle <= max_angle {
+        return target;
+    }
+
+    let sign = direction.cross(target).dot(up).signum();
+    Quat::from_axis_angle(up, sign * max_angle) * direction
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_bootstrap::Block;
+
+    use super::*;
+    use crate::feel::FeelHarness;
+
+    const PUSH_TICKS: u32 = 20;
+
+    #[test]
+    fn skating_from_rest_moves() {
+        let mut harness = FeelHarness::new(Block::Skate);
+        assert!(harness.speed() < 0.1, "settled at {}", harness.speed());
+
+        harness.hold(Vec2::X);
+        harness.run(PUSH_TICKS);
+        assert!(
+            harness.speed() >= MIN_SKATE_SPEED * 0.9,
+            "skating at {}",
+            harness.speed()
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::MovementIntent;
+use bevy_physics::*;
+
+use crate::{crouch::Stance, Player};
+
+pub struct SprintPlugin;
+
+impl Plugin for SprintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(PhysicsLabel::PreUpdate, sprint)
+            .add_snapshot_component::<Sprint>()
+            .add_snapshot_component::<Stamina>();
+    }
+}
+
+#[derive(Component, Default, Clone)]
+pub struct Sprint(bool);
+
+impl Sprint {
+    pub fn speed(&self) -> f32 {
+        if self.0 {
+            SPRINT_SPEED
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct Stamina {
+    current: f32,
+    regen_delay: f32,
+    exhausted: bool,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: STAMINA_MAX,
+            regen_delay: 0.0,
+            exhausted: false,
+        }
+    }
+}
+
+const SPRINT_SPEED: f32 = 1.6;
+const STAMINA_MAX: f32 = 3.0;
+const STAMINA_DRAIN: f32 = 1.0;
+const STAMINA_REGEN: f32 = 1.5;
+const STAMINA_REGEN_DELAY: f32 = 1.0;
+
+fn sprint(
+    mut player_q: Query<(&mut Sprint, &mut Stamina, &Stance, &MovementIntent), With<Player>>,
+    tick: Res<PhysicsTick>,
+) {
+    let dt = tick.delta();
+
+    for (mut sprint, mut stamina, stance, intent) in &mut player_q {
+        if !intent.sprint.held() {
+            stamina.exhausted = false;
+        }
+
+        let sprinting = intent.sprint.held()
+            && !intent.is_zero()
+            && *stance == Stance::Standing
+            && !stamina.exhausted;
+
+        if sprinting {
+            stamina.current = (stamina.current - STAMINA_DRAIN * dt).max(0.0);
+            stamina.regen_delay = STAMINA_REGEN_DELAY;
+
+            if stamina.current == 0.0 {
+                stamina.exhausted = true;
+            }
+        } else if stamina.regen_delay > 0.0 {
+            stamina.regen_delay -= dt;
+        } else {
+            stamina.current = (stamina.current + STAMINA_REGEN * dt).min(STAMINA_MAX);
+        }
+
+        if sprint.0 != sprinting {
+            sprint.0 = sprinting;
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use bevy_bootstrap::{Block, GravityField, InputDirection, MovementIntent};
+use bevy_extensions::*;
+use bevy_physics::*;
+
+use crate::{crouch::Stance, AccelerationScale, ControllerTuning, GroundState, Player, SpeedScale};
+
+pub struct SwimPlugin;
+
+impl Plugin for SwimPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_physics_system(
+            PhysicsLabel::PreUpdate,
+            submerge
+                .after(crate::set_ground_state)
+                .before(crate::on_ground_change),
+        )
+        .add_physics_system(PhysicsLabel::Update, swimming)
+        .add_snapshot_component::<Swimmer>();
+    }
+}
+
+#[derive(Component, Default, Clone)]
+pub struct Swimmer {
+    submersion: f32,
+}
+
+const SWIM_DEPTH: f32 = 0.5;
+const BUOYANCY: f32 = 1.5;
+// Jump swims up towards the surface and crouch dives
+const VERTICAL_SWIM_SPEED: f32 = 3.0;
+
+type SubmergeQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Swimmer,
+        &'static mut GroundState,
+        &'static Transform,
+        &'static GravityField,
+    ),
+    With<Player>,
+>;
+
+fn submerge(
+    mut player_q: SubmergeQuery,
+    water_q: Query<(&Block, &Transform), Without<Player>>,
+    physics: Res<PhysicsContext>,
+) {
+    for (player, mut swimmer, mut ground_state, transform, gravity_field) in &mut player_q {
+        let up = gravity_field.up(transform.translation);
+        // Water blocks are boxes, so the surface is the highest face along `up`
+        let surface = physics
+            .intersections_with(player)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .filter_map(|(e1, e2, _)| water_q.get(if e1 == player { e2 } else { e1 }).ok())
+            .filter(|(block, _)| matches!(block, Block::Water))
+            .map(|(_, water)| water.translation.vertical(up) + (water.scale * 0.5).dot(up.abs()))
+            .reduce(f32::max);
+
+        // Measured against the standing height, so crouching to dive doesn't
+        // change the buoyancy
+        swimmer.submersion = surface.map_or(0.0, |surface| {
+            ((surface - transform.translation.vertical(up)) / Stance::Standing.height())
+                .clamp(0.0, 1.0)
+        });
+
+        match *ground_state {
+            GroundState::Hanging | GroundState::Climbing => {}
+            GroundState::Swimming => {
+                if swimmer.submersion < SWIM_DEPTH {
+                    *ground_state = GroundState::None;
+                }
+            }
+            _ => {
+                if swimmer.submersion >= SWIM_DEPTH {
+                    *ground_state = GroundState::Swimming;
+                }
+            }
+        }
+    }
+}
+
+type SwimmingQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Velocity,
+        &'static Swimmer,
+        &'static GroundState,
+        &'static SpeedScale,
+        &'static AccelerationScale,
+        &'static InputDirection,
+        &'static MovementIntent,
+        &'static GravityField,
+        &'static Transform,
+    ),
+    With<Player>,
+>;
+
+fn swimming(mut player_q: SwimmingQuery, tuning: Res<ControllerTuning>, tick: Res<PhysicsTick>) {
+    for (
+        mut velocity,
+        swimmer,
+        ground_state,
+        speed_scale,
+        acceleration_scale,
+        input_direction,
+        intent,
+        gravity_field,
+        transform,
+    ) in &mut player_q
+    {
+        let dt = tick.delta();
+        let up = gravity_field.up(transform.translation);
+        let vertical_input = intent.jump.held() as i8 - intent.crouch.held() as i8;
+
+        // Diving swims against the buoyancy instead of being carried by it
+        if vertical_input >= 0 {
+            velocity.linvel += up * tuning.gravity * BUOYANCY * swimmer.submersion * dt;
+        }
+
+        if *ground_state != GroundState::Swimming {
+            continue;
+        }
+
+        // Without vertical input the buoyancy decides whether to float or sink
+        let vertical = if vertical_input == 0 {
+            velocity.linvel.vertical(up)
+        } else {
+            vertical_input as f32 * VERTICAL_SWIM_SPEED
+        };
+        let horizontal = input_direction.get().horizontal(up) * tuning.speed * speed_scale.0;
+        let target = horizontal + up * vertical;
+
+        let acceleration = tuning.acceleration * acceleration_scale.0 * dt;
+        velocity.linvel = velocity.linvel.move_towards(target, acceleration);
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use bevy_actions::{PlanProgress, PlanState};
+use bevy_bootstrap::{ButtonState, MovementIntent};
+
+const MAX_PACKET_SIZE: usize = 1200;
+
+// Artificial conditions applied to every packet a link sends. Losses are drawn
+// from `seed`, so the same packets are dropped on every run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LinkConditions {
+    pub latency: Duration,
+    pub loss: f32,
+    pub seed: u64,
+}
+
+// Delays are measured on the caller's clock, e.g. `Time::elapsed`, rather than
+// the wall clock
+pub struct Link {
+    socket: UdpSocket,
+    conditions: LinkConditions,
+    seed: u64,
+    outgoing: Vec<(Duration, SocketAddr, Vec<u8>)>,
+}
+
+impl Link {
+    pub fn bind(addr: SocketAddr, conditions: LinkConditions) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            conditions,
+            seed: conditions.seed,
+            outgoing: Vec::new(),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn send(&mut self, to: SocketAddr, packet: &Packet, now: Duration) {
+        if self.conditions.loss > 0.0 {
+            let rng = fastrand::Rng::with_seed(self.seed);
+            self.seed = rng.u64(..);
+            if rng.f32() < self.conditions.loss {
+                return;
+            }
+        }
+
+        let due = now + self.conditions.latency;
+        self.outgoing.push((due, to, packet.encode()));
+    }
+
+    pub fn flush(&mut self, now: Duration) {
+        let socket = &self.socket;
+        self.outgoing.retain(|(due, to, bytes)| {
+            if *due > now {
+                return true;
+            }
+
+            if let Err(err) = socket.send_to(bytes, to) {
+                warn!("failed to send packet to {to}: {err}");
+            }
+            false
+        });
+    }
+
+    pub fn receive(&mut self) -> Vec<(SocketAddr, Packet)> {
+        let mut packets = Vec::new();
+        let mut buffer = [0; MAX_PACKET_SIZE];
+
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, from)) => match Packet::decode(&buffer[..len]) {
+                    Some(packet) => packets.push((from, packet)),
+                    None => warn!("dropped malformed packet from {from}"),
+                },
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    warn!("failed to receive packet: {err}");
+                    break;
+                }
+            }
+        }
+
+        packets
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputFrame {
+    pub tick: u64,
+    pub intent: MovementIntent,
+    pub direction: Vec3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CharacterState {
+    pub id: u8,
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+// A kinematic block spawned with the level, identified by its place in the
+// level's spawn order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlatformState {
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub plan: Option<PlanState>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Packet {
+    Hello,
+    Welcome {
+        id: u8,
+    },
+    Input {
+        inputs: Vec<InputFrame>,
+    },
+    State {
+        ack: u64,
+        characters: Vec<CharacterState>,
+        platforms: Vec<PlatformState>,
+    },
+}
+
+impl Packet {
+    fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer(Vec::new());
+
+        match self {
+            Packet::Hello => writer.u8(0),
+            Packet::Welcome { id } => {
+                writer.u8(1);
+                writer.u8(*id);
+            }
+            Packet::Input { inputs } => {
+                writer.u8(2);
+                writer.u8(inputs.len() as u8);
+                for input in inputs {
+                    writer.u64(input.tick);
+                    writer.vec2(input.intent.movement);
+                    writer.u8(input.intent.jump.bits());
+                    writer.u8(input.intent.dash.bits());
+                    writer.u8(input.intent.crouch.bits());
+                    writer.u8(input.intent.sprint.bits());
+                    writer.vec3(input.direction);
+                }
+            }
+            Packet::State {
+                ack,
+                characters,
+                platforms,
+            } => {
+                writer.u8(3);
+                writer.u64(*ack);
+                writer.u8(characters.len() as u8);
+                for character in characters {
+                    writer.u8(character.id);
+                    writer.vec3(character.position);
+                    writer.vec3(character.velocity);
+                }
+                writer.u8(platforms.len() as u8);
+                for platform in platforms {
+                    writer.vec3(platform.position);
+                    writer.quat(platform.rotation);
+                    writer.plan(platform.plan);
+                }
+            }
+        }
+
+        writer.0
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+
+        let packet = match reader.u8()? {
+            0 => Packet::Hello,
+            1 => Packet::Welcome { id: reader.u8()? },
+            2 => {
+                let len = reader.u8()?;
+                let mut inputs = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    inputs.push(InputFrame {
+                        tick: reader.u64()?,
+                        intent: MovementIntent {
+                            movement: reader.vec2()?,
+                            jump: ButtonState::from_bits(reader.u8()?),
+                            dash: ButtonState::from_bits(reader.u8()?),
+                            crouch: ButtonState::from_bits(reader.u8()?),
+                            sprint: ButtonState::from_bits(reader.u8()?),
+                        },
+                        direction: reader.vec3()?,
+                    });
+                }
+                Packet::Input { inputs }
+            }
+            3 => {
+                let ack = reader.u64()?;
+                let len = reader.u8()?;
+                let mut characters = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    characters.push(CharacterState {
+                        id: reader.u8()?,
+                        position: reader.vec3()?,
+                        velocity: reader.vec3()?,
+                    });
+                }
+                let len = reader.u8()?;
+                let mut platforms = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    platforms.push(PlatformState {
+                        position: reader.vec3()?,
+                        rotation: reader.quat()?,
+                        plan: reader.plan()?,
+                    });
+                }
+                Packet::State {
+                    ack,
+                    characters,
+                    platforms,
+                }
+            }
+            _ => return None,
+        };
+
+        Some(packet)
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn vec2(&mut self, value: Vec2) {
+        self.f32(value.x);
+        self.f32(value.y);
+    }
+
+    fn vec3(&mut self, value: Vec3) {
+        self.f32(value.x);
+        self.f32(value.y);
+        self.f32(value.z);
+    }
+
+    fn quat(&mut self, value: Quat) {
+        for value in value.to_array() {
+            self.f32(value);
+        }
+    }
+
+    fn plan(&mut self, value: Option<PlanState>) {
+        let state = match value {
+            Some(state) => state,
+            None => return self.u8(0),
+        };
+
+        self.u8(1);
+        self.u8(state.current as u8);
+        match state.progress {
+            None => self.u8(0),
+            Some(PlanProgress::Wait { remaining }) => {
+                self.u8(1);
+                self.f32(remaining);
+            }
+            Some(PlanProgress::Lerp { from, elapsed }) => {
+                self.u8(2);
+                self.vec3(from.translation);
+                self.quat(from.rotation);
+                self.vec3(from.scale);
+                self.u64(elapsed.as_nanos() as u64);
+            }
+        }
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.0.len() < N {
+            return None;
+        }
+
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        bytes.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|bytes| bytes[0])
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.take().map(f32::from_le_bytes)
+    }
+
+    fn vec2(&mut self) -> Option<Vec2> {
+        Some(Vec2::new(self.f32()?, self.f32()?))
+    }
+
+    fn vec3(&mut self) -> Option<Vec3> {
+        Some(Vec3::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+
+    fn quat(&mut self) -> Option<Quat> {
+        Some(Quat::from_xyzw(
+            self.f32()?,
+            self.f32()?,
+            self.f32()?,
+            self.f32()?,
+        ))
+    }
+
+    fn plan(&mut self) -> Option<Option<PlanState>> {
+        if self.u8()? == 0 {
+            return Some(None);
+        }
+
+        let current = self.u8()? as usize;
+        let progress = match self.u8()? {
+            0 => None,
+            1 => Some(PlanProgress::Wait {
+                remaining: self.f32()?,
+            }),
+            2 => Some(PlanProgress::Lerp {
+                from: Transform {
+                    translation: self.vec3()?,
+                    rotation: self.quat()?,
+                    scale: self.vec3()?,
+                },
+                elapsed: Duration::from_nanos(self.u64()?),
+            }),
+            _ => return None,
+        };
+
+        Some(Some(PlanState { current, progress }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packets_round_trip() {
+        let mut jump = ButtonState::default();
+        jump.latch(true, true, false);
+
+        let packets = [
+            Packet::Hello,
+            Packet::Welcome { id: 3 },
+            Packet::Input {
+                inputs: vec![
+                    InputFrame {
+                        tick: 41,
+                        intent: MovementIntent {
+                            movement: Vec2::new(0.6, -0.8),
+                            jump,
+                            ..Default::default()
+                        },
+                        direction: Vec3::new(0.6, 0.0, 0.8),
+                    },
+                    InputFrame {
+                        tick: 42,
+                        intent: MovementIntent::default(),
+                        direction: Vec3::ZERO,
+                    },
+                ],
+            },
+            Packet::State {
+                ack: u64::MAX,
+                characters: vec![CharacterState {
+                    id: 1,
+                    position: Vec3::new(1.0, 2.5, -3.25),
+                    velocity: Vec3::new(-0.1, 9.81, 1e-7),
+                }],
+                platforms: vec![
+                    PlatformState {
+                        position: Vec3::X * 5.0,
+                        rotation: Quat::from_rotation_x(1.2),
+                        plan: None,
+                    },
+                    PlatformState {
+                        position: Vec3::new(-5.5, 0.5, -2.5),
+                        rotation: Quat::IDENTITY,
+                        plan: Some(PlanState {
+                            current: 1,
+                            progress: Some(PlanProgress::Lerp {
+                                from: Transform::from_xyz(-5.5, 0.5, -2.5),
+                                elapsed: Duration::from_nanos(1_234_567_891),
+                            }),
+                        }),
+                    },
+                    PlatformState {
+                        position: Vec3::ZERO,
+                        rotation: Quat::IDENTITY,
+                        plan: Some(PlanState {
+                            current: 2,
+                            progress: Some(PlanProgress::Wait { remaining: 0.35 }),
+                        }),
+                    },
+                ],
+            },
+        ];
+
+        for packet in packets {
+            assert_eq!(Packet::decode(&packet.encode()), Some(packet));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_packets() {
+        let bytes = Packet::Welcome { id: 3 }.encode();
+        assert_eq!(Packet::decode(&bytes[..1]), None);
+        assert_eq!(Packet::decode(&[]), None);
+    }
+}
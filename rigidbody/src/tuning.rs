@@ -0,0 +1,131 @@
+use bevy::{prelude::*, reflect::TypeUuid};
+use bevy_bootstrap::*;
+use serde::Deserialize;
+
+pub const TUNING_PATH: &str = "rigidbody.tuning.ron";
+
+#[derive(Resource, TypeUuid, Deserialize, Debug, Clone, PartialEq)]
+#[uuid = "f75ac25a-d484-4c5c-83f8-b87591e72bf5"]
+pub struct ControllerTuning {
+    pub speed: f32,
+    pub acceleration: f32,
+    pub damping: f32,
+    pub gravity: f32,
+    pub jump_height: f32,
+    pub rotation_speed: f32,
+    pub airborne: Scalars,
+    pub hanging: Scalars,
+    pub climbing: Scalars,
+    pub swimming: Scalars,
+    pub surfaces: Surfaces,
+}
+
+// Used until the asset has loaded, and whenever it fails to
+impl Default for ControllerTuning {
+    fn default() -> Self {
+        Self {
+            speed: 10.0,
+            acceleration: 30.0,
+            // Plain ground damping, which the airborne and swimming scalars are
+            // relative to since default surfaces scale it by 1.0
+            damping: 2.0,
+            gravity: 9.81,
+            jump_height: 3.0,
+            rotation_speed: 15.0,
+            airborne: Scalars {
+                speed: 1.0,
+                acceleration: 0.2,
+                damping: 0.05,
+                gravity: 1.1,
+                jump_height: 1.0,
+            },
+            hanging: Scalars {
+                speed: 0.0,
+                acceleration: 0.0,
+                damping: 0.0,
+                gravity: 0.0,
+                jump_height: 0.0,
+            },
+            climbing: Scalars {
+                speed: 0.0,
+                acceleration: 0.0,
+                damping: 0.0,
+                gravity: 0.0,
+                jump_height: 0.0,
+            },
+            swimming: Scalars {
+                speed: 0.5,
+                acceleration: 0.5,
+                damping: 1.5,
+                gravity: 1.0,
+                jump_height: 0.0,
+            },
+            surfaces: Surfaces::from([
+                (
+                    "ice",
+                    SurfaceMaterial {
+                        speed: 1.5,
+                        acceleration: 0.2,
+                        damping: 0.0,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "skate",
+                    SurfaceMaterial {
+                        damping: 0.0,
+                        mode: MovementMode::Forward { turn_rate: 2.0 },
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        }
+    }
+}
+
+impl ControllerTuning {
+    // Launch speed that peaks `height` above the take-off point
+    pub fn jump_speed(&self, gravity_scale: f32, height: f32) -> f32 {
+        f32::sqrt(2.0 * self.gravity * gravity_scale * height)
+    }
+}
+
+impl Tuning for ControllerTuning {
+    const EXTENSION: &'static str = "tuning.ron";
+
+    fn validate(&self) -> Result<(), String> {
+        non_negative("speed", self.speed)?;
+        non_negative("acceleration", self.acceleration)?;
+        non_negative("damping", self.damping)?;
+        non_negative("gravity", self.gravity)?;
+        non_negative("jump_height", self.jump_height)?;
+        non_negative("rotation_speed", self.rotation_speed)?;
+
+        for (name, scalars) in [
+            ("airborne", &self.airborne),
+            ("hanging", &self.hanging),
+            ("climbing", &self.climbing),
+            ("swimming", &self.swimming),
+        ] {
+            scalars.validate().map_err(|err| format!("{name}.{err}"))?;
+        }
+
+        self.surfaces
+            .validate()
+            .map_err(|err| format!("surfaces.{err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The fallback and the shipped asset are kept in step
+    #[test]
+    fn asset_matches_default() {
+        let tuning: ControllerTuning =
+            ron::de::from_str(include_str!("../../assets/rigidbody.tuning.ron")).unwrap();
+        assert_eq!(tuning.validate(), Ok(()));
+        assert_eq!(tuning, ControllerTuning::default());
+    }
+}